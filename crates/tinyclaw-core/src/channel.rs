@@ -0,0 +1,514 @@
+//! Channel client primitives and the composable agent-resolution pipeline
+//! that feeds the router a live view of which agents are currently
+//! reachable.
+//!
+//! The resolution side is modeled as a small `Resolve` trait: each source
+//! (queue polling today, HTTP/Slack/Discord transports later) owns its own
+//! reconnect logic and emits *batched snapshot* [`Update`]s rather than
+//! single deltas, so a dropped connection can never leave the router with
+//! stale routes — a reconnect just re-sends a fresh `Update::Reset`. An
+//! [`AggregateResolver`] composes several sources into the one stream the
+//! router actually consumes, deduping by agent id and isolating per-source
+//! failures so a flaky transport doesn't take down the others.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::mpsc;
+
+use crate::message::Channel;
+use crate::queue::QueueDir;
+
+/// Thin handle to a channel transport (Discord/Telegram/etc. client). The
+/// concrete per-platform clients live alongside their channel adapters;
+/// this struct only carries what the queue processor needs to address one.
+#[derive(Debug, Clone)]
+pub struct ChannelClient {
+    pub channel: Channel,
+    pub label: String,
+}
+
+impl ChannelClient {
+    pub fn new(channel: Channel, label: impl Into<String>) -> Self {
+        Self {
+            channel,
+            label: label.into(),
+        }
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock before UNIX epoch")
+        .as_millis() as u64
+}
+
+static MESSAGE_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Generate a unique, roughly sortable message id: `<millis>-<counter>`.
+pub fn generate_message_id() -> String {
+    let seq = MESSAGE_ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:x}", now_millis(), seq)
+}
+
+/// One endpoint an agent can currently be reached at — the unit the
+/// resolution pipeline tracks and hands to the router.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AgentEndpoint {
+    pub agent_id: String,
+    pub channel: Channel,
+}
+
+/// A batched snapshot update emitted by a [`Resolve`] source.
+///
+/// `Reset` carries the *full* active-agent set and is used both for the
+/// initial snapshot and to re-synchronize after a reconnect, so consumers
+/// never have to reason about which deltas they might have missed.
+#[derive(Debug, Clone)]
+pub enum Update {
+    Reset(Vec<AgentEndpoint>),
+    Add(Vec<AgentEndpoint>),
+    Remove(Vec<AgentEndpoint>),
+}
+
+/// A composable source of agent-endpoint updates.
+///
+/// Conceptually this is an async `Service`: a request (just `capacity`,
+/// today) in, a stream of updates out. Implementations that lose their
+/// underlying connection are expected to reconnect internally and emit a
+/// fresh `Update::Reset` rather than closing the channel, so a single
+/// flaky transport degrades gracefully instead of leaking stale routes.
+pub trait Resolve: Send + Sync {
+    /// Name used in tracing output when this source fails or reconnects.
+    fn name(&self) -> &str;
+
+    /// Start resolving. Spawns whatever background work is needed and
+    /// returns immediately with the receiving end of a bounded channel,
+    /// which provides backpressure: a slow consumer stalls this source's
+    /// producer rather than letting updates pile up unbounded.
+    fn resolve(&self, capacity: usize) -> mpsc::Receiver<Update>;
+}
+
+/// Resolves active agents by polling a [`QueueDir`]'s outgoing responses
+/// for a given prefix, the same signal `chat_handler` already reads.
+pub struct QueuePollResolver {
+    queue: Arc<QueueDir>,
+    prefix: String,
+    interval: Duration,
+}
+
+impl QueuePollResolver {
+    pub fn new(queue: Arc<QueueDir>, prefix: impl Into<String>, interval: Duration) -> Self {
+        Self {
+            queue,
+            prefix: prefix.into(),
+            interval,
+        }
+    }
+}
+
+impl Resolve for QueuePollResolver {
+    fn name(&self) -> &str {
+        "queue"
+    }
+
+    fn resolve(&self, capacity: usize) -> mpsc::Receiver<Update> {
+        let (tx, rx) = mpsc::channel(capacity);
+        let queue = self.queue.clone();
+        let prefix = self.prefix.clone();
+        let interval = self.interval;
+
+        tokio::spawn(async move {
+            loop {
+                match queue.poll_outgoing(&prefix).await {
+                    Ok(responses) => {
+                        let snapshot: Vec<AgentEndpoint> = responses
+                            .iter()
+                            .filter_map(|(_, resp)| {
+                                resp.agent.clone().map(|agent_id| AgentEndpoint {
+                                    agent_id,
+                                    channel: resp.channel.clone(),
+                                })
+                            })
+                            .collect();
+                        // A full snapshot every poll: any drop/reconnect on
+                        // the underlying queue is indistinguishable from a
+                        // normal poll here, which is exactly the point —
+                        // the consumer always gets a fresh, authoritative
+                        // Reset rather than having to detect a gap itself.
+                        if tx.send(Update::Reset(snapshot)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            resolver = "queue",
+                            error = %err,
+                            "poll_outgoing failed, will retry"
+                        );
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        rx
+    }
+}
+
+/// Composes several [`Resolve`] sources into one deduped stream.
+///
+/// Each source runs in its own task; a source that errors or disconnects
+/// only affects its own contribution to the aggregate set and is logged,
+/// not propagated as a failure of the whole pipeline.
+pub struct AggregateResolver {
+    sources: Vec<Box<dyn Resolve>>,
+}
+
+impl AggregateResolver {
+    pub fn new(sources: Vec<Box<dyn Resolve>>) -> Self {
+        Self { sources }
+    }
+
+    /// Start every source and return one unified stream of updates, deduped
+    /// by agent id across sources (first source to claim an id — meaning
+    /// the source earliest in the `sources` list passed to [`Self::new`] —
+    /// wins ties) via a `claims` table shared across every source's task.
+    ///
+    /// Each source's own `Update::Reset` is first translated into
+    /// `Add`/`Remove` scoped to exactly what changed *for that source*, by
+    /// diffing it against the source's previously-seen set — a reconnect
+    /// on source A must never wipe out source B's endpoints, but
+    /// `Update::Reset` downstream (see `reconcile`) means "clear
+    /// everything", so a raw Reset can never be forwarded as-is once more
+    /// than one source is in play. Those per-source deltas are then run
+    /// through `claims` so an id is only ever added/removed downstream
+    /// when doing so actually changes who the winning source for that id
+    /// is — e.g. if source B also claims an id source A just dropped, the
+    /// id is never removed downstream at all, it just silently hands over
+    /// to B.
+    pub fn resolve(&self, capacity: usize) -> mpsc::Receiver<Update> {
+        let (tx, rx) = mpsc::channel(capacity);
+        let claims: Arc<Mutex<HashMap<String, BTreeMap<usize, AgentEndpoint>>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+
+        for (source_idx, source) in self.sources.iter().enumerate() {
+            let mut source_rx = source.resolve(capacity);
+            let tx = tx.clone();
+            let name = source.name().to_string();
+            let claims = claims.clone();
+            let mut per_source_active: HashSet<AgentEndpoint> = HashSet::new();
+
+            tokio::spawn(async move {
+                while let Some(update) = source_rx.recv().await {
+                    let (removed, added) = match &update {
+                        Update::Reset(endpoints) => {
+                            let new_active: HashSet<AgentEndpoint> =
+                                endpoints.iter().cloned().collect();
+                            let removed: Vec<AgentEndpoint> =
+                                per_source_active.difference(&new_active).cloned().collect();
+                            let added: Vec<AgentEndpoint> =
+                                new_active.difference(&per_source_active).cloned().collect();
+                            per_source_active = new_active;
+                            (removed, added)
+                        }
+                        Update::Add(endpoints) => {
+                            per_source_active.extend(endpoints.iter().cloned());
+                            (Vec::new(), endpoints.clone())
+                        }
+                        Update::Remove(endpoints) => {
+                            for endpoint in endpoints {
+                                per_source_active.remove(endpoint);
+                            }
+                            (endpoints.clone(), Vec::new())
+                        }
+                    };
+
+                    let (removed, added) = {
+                        let mut claims = claims.lock().unwrap();
+                        let mut out_removed = Vec::new();
+                        let mut out_added = Vec::new();
+                        for endpoint in &removed {
+                            if let (Some(old), new) =
+                                release_claim(&mut claims, source_idx, &endpoint.agent_id)
+                            {
+                                out_removed.push(old);
+                                out_added.extend(new);
+                            }
+                        }
+                        for endpoint in added {
+                            let (old, new) = record_claim(&mut claims, source_idx, endpoint);
+                            out_removed.extend(old);
+                            if let Some(new) = new {
+                                out_added.push(new);
+                            }
+                        }
+                        (out_removed, out_added)
+                    };
+
+                    if !removed.is_empty() && tx.send(Update::Remove(removed)).await.is_err() {
+                        break;
+                    }
+                    if !added.is_empty() && tx.send(Update::Add(added)).await.is_err() {
+                        break;
+                    }
+                }
+                tracing::warn!(
+                    resolver = %name,
+                    active_when_dropped = per_source_active.len(),
+                    "resolver source stream ended"
+                );
+            });
+        }
+
+        rx
+    }
+}
+
+/// Record that `source_idx` now claims `endpoint.agent_id` (with the
+/// endpoint `endpoint`). Returns the endpoint that should be removed from
+/// and/or added to the aggregate's downstream view, if the winning
+/// (lowest source index) claim for this id actually changed.
+fn record_claim(
+    claims: &mut HashMap<String, BTreeMap<usize, AgentEndpoint>>,
+    source_idx: usize,
+    endpoint: AgentEndpoint,
+) -> (Option<AgentEndpoint>, Option<AgentEndpoint>) {
+    let agent_claims = claims.entry(endpoint.agent_id.clone()).or_default();
+    let previous_winner = agent_claims.values().next().cloned();
+    agent_claims.insert(source_idx, endpoint);
+    let new_winner = agent_claims.values().next().cloned();
+    if previous_winner == new_winner {
+        (None, None)
+    } else {
+        (previous_winner, new_winner)
+    }
+}
+
+/// Release `source_idx`'s claim on `agent_id`. Returns the endpoint that
+/// should be removed from and/or added to the aggregate's downstream
+/// view, if the winning claim for this id actually changed (e.g. it falls
+/// through to another source still claiming it, or disappears entirely).
+fn release_claim(
+    claims: &mut HashMap<String, BTreeMap<usize, AgentEndpoint>>,
+    source_idx: usize,
+    agent_id: &str,
+) -> (Option<AgentEndpoint>, Option<AgentEndpoint>) {
+    let Some(agent_claims) = claims.get_mut(agent_id) else {
+        return (None, None);
+    };
+    let previous_winner = agent_claims.values().next().cloned();
+    agent_claims.remove(&source_idx);
+    let new_winner = agent_claims.values().next().cloned();
+    if agent_claims.is_empty() {
+        claims.remove(agent_id);
+    }
+    if previous_winner == new_winner {
+        (None, None)
+    } else {
+        (previous_winner, new_winner)
+    }
+}
+
+/// Fold a sequence of per-source `Update`s into the deduped active set a
+/// router would maintain. Pulled out as a pure function so the dedup
+/// behavior is unit-testable without spinning up any tasks or transports.
+pub fn reconcile(active: &mut HashMap<String, AgentEndpoint>, update: &Update) {
+    match update {
+        Update::Reset(endpoints) => {
+            active.clear();
+            for endpoint in endpoints {
+                active.insert(endpoint.agent_id.clone(), endpoint.clone());
+            }
+        }
+        Update::Add(endpoints) => {
+            for endpoint in endpoints {
+                active.insert(endpoint.agent_id.clone(), endpoint.clone());
+            }
+        }
+        Update::Remove(endpoints) => {
+            for endpoint in endpoints {
+                active.remove(&endpoint.agent_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(id: &str) -> AgentEndpoint {
+        AgentEndpoint {
+            agent_id: id.to_string(),
+            channel: Channel::Http,
+        }
+    }
+
+    #[test]
+    fn reset_replaces_the_whole_active_set() {
+        let mut active = HashMap::new();
+        active.insert("stale".to_string(), endpoint("stale"));
+
+        reconcile(&mut active, &Update::Reset(vec![endpoint("a"), endpoint("b")]));
+
+        assert_eq!(active.len(), 2);
+        assert!(!active.contains_key("stale"));
+        assert!(active.contains_key("a"));
+        assert!(active.contains_key("b"));
+    }
+
+    #[test]
+    fn add_and_remove_apply_incrementally() {
+        let mut active = HashMap::new();
+        reconcile(&mut active, &Update::Add(vec![endpoint("a")]));
+        reconcile(&mut active, &Update::Add(vec![endpoint("b")]));
+        reconcile(&mut active, &Update::Remove(vec![endpoint("a")]));
+
+        assert_eq!(active.len(), 1);
+        assert!(active.contains_key("b"));
+    }
+
+    #[tokio::test]
+    async fn aggregate_resolver_surfaces_updates_from_all_sources() {
+        struct StaticResolver(&'static str, Vec<AgentEndpoint>);
+        impl Resolve for StaticResolver {
+            fn name(&self) -> &str {
+                self.0
+            }
+            fn resolve(&self, capacity: usize) -> mpsc::Receiver<Update> {
+                let (tx, rx) = mpsc::channel(capacity);
+                let endpoints = self.1.clone();
+                tokio::spawn(async move {
+                    let _ = tx.send(Update::Reset(endpoints)).await;
+                });
+                rx
+            }
+        }
+
+        let aggregate = AggregateResolver::new(vec![
+            Box::new(StaticResolver("one", vec![endpoint("a")])),
+            Box::new(StaticResolver("two", vec![endpoint("b")])),
+        ]);
+
+        let mut rx = aggregate.resolve(8);
+        let mut active = HashMap::new();
+        for _ in 0..2 {
+            if let Some(update) = rx.recv().await {
+                reconcile(&mut active, &update);
+            }
+        }
+
+        assert!(active.contains_key("a"));
+        assert!(active.contains_key("b"));
+    }
+
+    #[tokio::test]
+    async fn one_sources_reset_does_not_clear_another_sources_endpoints() {
+        struct ReconnectingResolver(&'static str, AgentEndpoint);
+        impl Resolve for ReconnectingResolver {
+            fn name(&self) -> &str {
+                self.0
+            }
+            fn resolve(&self, capacity: usize) -> mpsc::Receiver<Update> {
+                let (tx, rx) = mpsc::channel(capacity);
+                let endpoint = self.1.clone();
+                tokio::spawn(async move {
+                    // Simulate a reconnect: this source re-sends its own
+                    // (unchanged) full snapshot as a second Reset.
+                    let _ = tx.send(Update::Reset(vec![endpoint.clone()])).await;
+                    let _ = tx.send(Update::Reset(vec![endpoint])).await;
+                });
+                rx
+            }
+        }
+
+        let aggregate = AggregateResolver::new(vec![
+            Box::new(ReconnectingResolver("flaky", endpoint("a"))),
+            Box::new(ReconnectingResolver("stable", endpoint("b"))),
+        ]);
+
+        let mut rx = aggregate.resolve(16);
+        let mut active = HashMap::new();
+        // Both sources send two Resets each (one per reconnect), but the
+        // unchanged second Reset forwards nothing, so only two updates
+        // arrive; once both sources finish, `recv` returns `None`.
+        for _ in 0..4 {
+            if let Some(update) = rx.recv().await {
+                reconcile(&mut active, &update);
+            }
+        }
+
+        assert!(
+            active.contains_key("a"),
+            "source 'flaky' reconnecting should not drop its own endpoint"
+        );
+        assert!(
+            active.contains_key("b"),
+            "source 'flaky' reconnecting must not clear source 'stable's endpoint"
+        );
+    }
+
+    #[tokio::test]
+    async fn cross_source_dedup_keeps_id_alive_while_any_source_still_claims_it() {
+        struct SequencedResolver(&'static str, Vec<Vec<AgentEndpoint>>);
+        impl Resolve for SequencedResolver {
+            fn name(&self) -> &str {
+                self.0
+            }
+            fn resolve(&self, capacity: usize) -> mpsc::Receiver<Update> {
+                let (tx, rx) = mpsc::channel(capacity);
+                let snapshots = self.1.clone();
+                tokio::spawn(async move {
+                    for snapshot in snapshots {
+                        let _ = tx.send(Update::Reset(snapshot)).await;
+                    }
+                });
+                rx
+            }
+        }
+
+        let a_http = AgentEndpoint {
+            agent_id: "a".to_string(),
+            channel: Channel::Http,
+        };
+        let a_discord = AgentEndpoint {
+            agent_id: "a".to_string(),
+            channel: Channel::Discord,
+        };
+
+        let aggregate = AggregateResolver::new(vec![
+            // Declared first, so it's the id's winning source while it
+            // claims "a" — then reconnects and stops listing it.
+            Box::new(SequencedResolver("http", vec![vec![a_http.clone()], vec![]])),
+            // Claims "a" throughout and never reconnects.
+            Box::new(SequencedResolver("discord", vec![vec![a_discord.clone()]])),
+        ]);
+
+        let mut rx = aggregate.resolve(16);
+        let mut active = HashMap::new();
+        // "http" sends 2 Resets, "discord" sends 1; only updates that
+        // actually change the cross-source winner reach this stream, so
+        // drain until the receiver would block instead of assuming a
+        // fixed count.
+        while let Ok(update) = tokio::time::timeout(Duration::from_millis(200), rx.recv()).await {
+            match update {
+                Some(update) => reconcile(&mut active, &update),
+                None => break,
+            }
+        }
+
+        assert!(
+            active.contains_key("a"),
+            "source 'discord' still claims \"a\"; source 'http's reconnect must not drop it"
+        );
+        assert_eq!(
+            active.get("a").map(|e| &e.channel),
+            Some(&Channel::Discord),
+            "once 'http' drops its claim, 'discord's endpoint should be the one surfaced"
+        );
+    }
+}
@@ -1,21 +1,278 @@
-use axum::extract::State;
-use axum::http::StatusCode;
+mod patch;
+
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
-use axum::routing::{get, post};
+use axum::routing::{get, patch as patch_method, post};
 use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
-use tinyclaw_core::channel::{generate_message_id, now_millis};
+use tinyclaw_core::channel::{
+    generate_message_id, now_millis, reconcile, AggregateResolver, QueuePollResolver,
+};
 use tinyclaw_core::config::HttpSettings;
-use tinyclaw_core::message::{Channel, IncomingMessage};
+use tinyclaw_core::message::{AgentConfig, Channel, IncomingMessage, OutgoingMessage, TeamConfig};
 use tinyclaw_core::queue::QueueDir;
 use tower_http::cors::{Any, CorsLayer};
 
+use patch::{apply_json_patch, apply_merge_patch, JsonPatchOp};
+
+/// Arbitrary per-conversation state, keyed by `conversation_id`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConversationState {
+    #[serde(flatten)]
+    pub data: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A resource plus the version it carries for `ETag`/`If-Match` purposes.
+/// The version is bumped on every successful mutation.
+struct Versioned<T> {
+    value: T,
+    version: u64,
+}
+
+/// Version-tracked resource table backing the PATCH endpoints. Lives in
+/// memory for the lifetime of the process; the agent/team CRUD surface
+/// below (see its section comment) is responsible for writing a snapshot
+/// through to [`AGENT_STORE_PATH`] after each mutation, since there is no
+/// `Settings` writer visible in this crate to wire into instead.
+struct VersionedStore<T> {
+    items: RwLock<HashMap<String, Versioned<T>>>,
+}
+
+impl<T: Clone> VersionedStore<T> {
+    fn new() -> Self {
+        Self {
+            items: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn seed(&self, id: impl Into<String>, value: T) {
+        self.items
+            .write()
+            .unwrap()
+            .insert(id.into(), Versioned { value, version: 0 });
+    }
+
+    /// Like [`Self::seed`], but restoring a specific version — used when
+    /// repopulating the store from [`PersistedStore`] so `ETag`s issued
+    /// before a restart stay valid against the restored entries.
+    fn seed_with_version(&self, id: impl Into<String>, value: T, version: u64) {
+        self.items
+            .write()
+            .unwrap()
+            .insert(id.into(), Versioned { value, version });
+    }
+
+    fn get(&self, id: &str) -> Option<(T, u64)> {
+        self.items
+            .read()
+            .unwrap()
+            .get(id)
+            .map(|v| (v.value.clone(), v.version))
+    }
+
+    fn list(&self) -> Vec<(String, T, u64)> {
+        self.items
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(id, v)| (id.clone(), v.value.clone(), v.version))
+            .collect()
+    }
+
+    /// Insert `value` at `id` only if nothing is stored there yet.
+    fn create(&self, id: impl Into<String>, value: T) -> Option<(T, u64)> {
+        let mut items = self.items.write().unwrap();
+        let id = id.into();
+        if items.contains_key(&id) {
+            return None;
+        }
+        items.insert(id, Versioned { value: value.clone(), version: 0 });
+        Some((value, 0))
+    }
+
+    /// Create-or-replace at `id` (PUT semantics). Honors an optional
+    /// `If-Match` precondition against the *current* version if one is
+    /// already stored; a fresh id is always accepted regardless of
+    /// `if_match` since there is nothing to match against yet.
+    fn put(&self, id: impl Into<String>, if_match: Option<u64>, value: T) -> Result<(T, u64), u64> {
+        let mut items = self.items.write().unwrap();
+        let id = id.into();
+        match items.get_mut(&id) {
+            Some(entry) => {
+                if let Some(expected) = if_match {
+                    if expected != entry.version {
+                        return Err(entry.version);
+                    }
+                }
+                entry.value = value;
+                entry.version += 1;
+                Ok((entry.value.clone(), entry.version))
+            }
+            None => {
+                items.insert(id, Versioned { value: value.clone(), version: 0 });
+                Ok((value, 0))
+            }
+        }
+    }
+
+    /// Apply `mutate` to the stored value, bumping its version. `id` is
+    /// assumed to already exist (callers check presence up front so a
+    /// missing id can be reported as 404 rather than a version mismatch).
+    /// If `if_match` is `Some`, the current version must equal it or
+    /// `Ok(Err(current_version))` is returned.
+    fn update<E>(
+        &self,
+        id: &str,
+        if_match: Option<u64>,
+        mutate: impl FnOnce(&mut T) -> Result<(), E>,
+    ) -> Result<Result<(T, u64), u64>, E> {
+        let mut items = self.items.write().unwrap();
+        let entry = items.get_mut(id).expect("caller checked id exists");
+        if let Some(expected) = if_match {
+            if expected != entry.version {
+                return Ok(Err(entry.version));
+            }
+        }
+        mutate(&mut entry.value)?;
+        entry.version += 1;
+        Ok(Ok((entry.value.clone(), entry.version)))
+    }
+
+    /// Remove `id`, honoring an optional `If-Match` precondition. Returns
+    /// `Ok(true)` if removed, `Ok(false)` if absent, `Err(current_version)`
+    /// on a precondition mismatch.
+    fn remove(&self, id: &str, if_match: Option<u64>) -> Result<bool, u64> {
+        let mut items = self.items.write().unwrap();
+        match items.get(id) {
+            None => Ok(false),
+            Some(entry) => {
+                if let Some(expected) = if_match {
+                    if expected != entry.version {
+                        return Err(entry.version);
+                    }
+                }
+                items.remove(id);
+                Ok(true)
+            }
+        }
+    }
+}
+
+fn etag(version: u64) -> String {
+    format!("\"{}\"", version)
+}
+
+fn parse_if_match(headers: &HeaderMap) -> Option<u64> {
+    let raw = headers.get("if-match")?.to_str().ok()?;
+    raw.trim().trim_matches('"').parse().ok()
+}
+
 #[derive(Clone)]
 struct AppState {
     queue: Arc<QueueDir>,
+    agents: Arc<VersionedStore<AgentConfig>>,
+    teams: Arc<VersionedStore<TeamConfig>>,
+    conversations: Arc<VersionedStore<ConversationState>>,
+    store_path: Arc<std::path::PathBuf>,
+}
+
+/// Default on-disk location the agent/team CRUD surface persists to,
+/// relative to the current directory — the same sidecar-file approach
+/// `merge-engine` uses for its conflict-tracking state, since there is no
+/// `Settings` writer visible in this crate to write through to instead.
+const AGENT_STORE_PATH: &str = ".tinyclaw/agents.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedEntry<T> {
+    id: String,
+    value: T,
+    version: u64,
+}
+
+/// On-disk snapshot of the agent/team CRUD surface, written after every
+/// successful mutation so created/updated/deleted resources survive a
+/// process restart instead of living only in the in-memory
+/// [`VersionedStore`]s.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedStore {
+    agents: Vec<PersistedEntry<AgentConfig>>,
+    teams: Vec<PersistedEntry<TeamConfig>>,
+}
+
+/// Load a previously-saved [`PersistedStore`], or `None` if the file is
+/// missing or fails to parse (a fresh deployment, or one that has never
+/// had a CRUD mutation yet).
+fn load_persisted_store(path: &std::path::Path) -> Option<PersistedStore> {
+    let text = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// Write the current contents of `agents`/`teams` to `path`, creating its
+/// parent directory as needed. Best-effort: a write failure is silently
+/// skipped rather than failing the request that triggered it, since the
+/// in-memory store (and the response already sent) stays correct either
+/// way.
+fn save_persisted_store(
+    path: &std::path::Path,
+    agents: &VersionedStore<AgentConfig>,
+    teams: &VersionedStore<TeamConfig>,
+) {
+    let store = PersistedStore {
+        agents: agents
+            .list()
+            .into_iter()
+            .map(|(id, value, version)| PersistedEntry { id, value, version })
+            .collect(),
+        teams: teams
+            .list()
+            .into_iter()
+            .map(|(id, value, version)| PersistedEntry { id, value, version })
+            .collect(),
+    };
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() && std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+    if let Ok(json) = serde_json::to_string_pretty(&store) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Checks that can be done without a live config/router handle: an agent
+/// needs a non-empty name, and a team may only reference agents which
+/// actually exist. Id uniqueness is enforced by `VersionedStore::create`.
+fn validate_agent_config(agent: &AgentConfig) -> Result<(), ApiError> {
+    if agent.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("agent name must not be empty".into()));
+    }
+    Ok(())
+}
+
+fn validate_team_config(team: &TeamConfig, known_agent_ids: &std::collections::HashSet<String>) -> Result<(), ApiError> {
+    if team.name.trim().is_empty() {
+        return Err(ApiError::BadRequest("team name must not be empty".into()));
+    }
+    for agent_id in &team.agents {
+        if !known_agent_ids.contains(agent_id) {
+            return Err(ApiError::BadRequest(format!(
+                "team {:?} references unknown agent {:?}",
+                team.name, agent_id
+            )));
+        }
+    }
+    if !team.leader_agent.is_empty() && !known_agent_ids.contains(&team.leader_agent) {
+        return Err(ApiError::BadRequest(format!(
+            "team {:?} leader_agent {:?} is not a known agent",
+            team.name, team.leader_agent
+        )));
+    }
+    Ok(())
 }
 
 #[derive(Debug, Deserialize)]
@@ -68,11 +325,45 @@ impl<E: Into<anyhow::Error>> From<E> for AppError {
 pub struct HttpServer {
     queue: Arc<QueueDir>,
     settings: HttpSettings,
+    agents: Vec<AgentConfig>,
+    teams: Vec<TeamConfig>,
+    store_path: std::path::PathBuf,
 }
 
 impl HttpServer {
     pub fn new(queue: Arc<QueueDir>, settings: HttpSettings) -> Self {
-        Self { queue, settings }
+        Self {
+            queue,
+            settings,
+            agents: Vec::new(),
+            teams: Vec::new(),
+            store_path: std::path::PathBuf::from(AGENT_STORE_PATH),
+        }
+    }
+
+    /// Seed the runtime agent store so the `/v1/agents` management
+    /// endpoints have something to operate on. Mirrors the `AgentConfig`s
+    /// loaded from `Settings` at startup. Overridden by whatever is already
+    /// on disk at the store path (see [`Self::with_store_path`]) if a
+    /// previous run has persisted CRUD changes there.
+    pub fn with_agents(mut self, agents: Vec<AgentConfig>) -> Self {
+        self.agents = agents;
+        self
+    }
+
+    /// Seed the runtime team store, mirroring the `TeamConfig`s loaded
+    /// from `Settings` at startup. Same override rule as
+    /// [`Self::with_agents`].
+    pub fn with_teams(mut self, teams: Vec<TeamConfig>) -> Self {
+        self.teams = teams;
+        self
+    }
+
+    /// Override where the agent/team CRUD surface persists to. Defaults to
+    /// [`AGENT_STORE_PATH`].
+    pub fn with_store_path(mut self, store_path: impl Into<std::path::PathBuf>) -> Self {
+        self.store_path = store_path.into();
+        self
     }
 
     pub async fn start(
@@ -84,17 +375,86 @@ impl HttpServer {
             .allow_methods(Any)
             .allow_headers(Any);
 
+        let agents = Arc::new(VersionedStore::new());
+        let teams = Arc::new(VersionedStore::new());
+        match load_persisted_store(&self.store_path) {
+            Some(persisted) if !persisted.agents.is_empty() || !persisted.teams.is_empty() => {
+                for entry in persisted.agents {
+                    agents.seed_with_version(entry.id, entry.value, entry.version);
+                }
+                for entry in persisted.teams {
+                    teams.seed_with_version(entry.id, entry.value, entry.version);
+                }
+            }
+            _ => {
+                for agent in &self.agents {
+                    agents.seed(agent.name.clone(), agent.clone());
+                }
+                for team in &self.teams {
+                    teams.seed(team.name.clone(), team.clone());
+                }
+            }
+        }
+
         let state = AppState {
             queue: self.queue.clone(),
+            agents,
+            teams,
+            conversations: Arc::new(VersionedStore::new()),
+            store_path: Arc::new(self.store_path.clone()),
         };
 
         let app = Router::new()
             .route("/v1/chat", post(chat_handler))
             .route("/v1/status", get(status_handler))
-            .route("/v1/reset", post(reset_handler))
+            .route(
+                "/v1/agents",
+                get(list_agents_handler).post(create_agent_handler),
+            )
+            .route(
+                "/v1/agents/:id",
+                get(get_agent_handler)
+                    .put(put_agent_handler)
+                    .patch(patch_agent_handler)
+                    .delete(delete_agent_handler),
+            )
+            .route("/v1/agents/:id/messages", get(agent_messages_handler))
+            .route(
+                "/v1/teams",
+                get(list_teams_handler).post(create_team_handler),
+            )
+            .route(
+                "/v1/teams/:id",
+                get(get_team_handler)
+                    .put(put_team_handler)
+                    .delete(delete_team_handler),
+            )
+            .route(
+                "/v1/conversations/:conversation_id",
+                patch_method(patch_conversation_handler).delete(delete_conversation_handler),
+            )
             .layer(cors)
             .with_state(state);
 
+        // Watch which agents are actively replying via the queue, through
+        // the composable resolver pipeline (`chat_handler` still polls for
+        // its own reply). Each queue poll is a fresh snapshot, which the
+        // aggregate turns into `Add`/`Remove` updates here, so this view
+        // never goes stale.
+        let resolver = AggregateResolver::new(vec![Box::new(QueuePollResolver::new(
+            self.queue.clone(),
+            "http_",
+            Duration::from_millis(500),
+        ))]);
+        let mut agent_updates = resolver.resolve(16);
+        tokio::spawn(async move {
+            let mut active = HashMap::new();
+            while let Some(update) = agent_updates.recv().await {
+                reconcile(&mut active, &update);
+                tracing::debug!(active_agents = active.len(), "agent resolution updated");
+            }
+        });
+
         let addr = SocketAddr::from(([0, 0, 0, 0], self.settings.port));
         tracing::info!("HTTP API listening on {}", addr);
 
@@ -113,7 +473,17 @@ impl HttpServer {
 async fn chat_handler(
     State(state): State<AppState>,
     Json(req): Json<ChatRequest>,
-) -> Result<Json<ChatResponse>, AppError> {
+) -> Result<Json<ChatResponse>, ApiError> {
+    if let Some(agent) = &req.agent {
+        // Route against the live `agents` store, not a snapshot taken at
+        // startup, so a `POST /v1/agents` lands immediately: the very next
+        // chat naming that id is routable with no restart and no separate
+        // reload step.
+        if state.agents.get(agent).is_none() {
+            return Err(ApiError::BadRequest(format!("unknown agent {:?}", agent)));
+        }
+    }
+
     let message_id = generate_message_id();
 
     let incoming = IncomingMessage {
@@ -172,10 +542,343 @@ async fn status_handler() -> Json<StatusResponse> {
     })
 }
 
-async fn reset_handler() -> Result<Json<serde_json::Value>, AppError> {
-    let reset_flag = std::path::Path::new(".tinyclaw/reset_flag");
-    tokio::fs::write(reset_flag, "reset").await?;
+/// Error type for the versioned resource endpoints — unlike [`AppError`]
+/// these need to distinguish 404/412 from generic 500s so `If-Match`
+/// semantics are actually observable to callers.
+enum ApiError {
+    NotFound,
+    PreconditionFailed { current_version: u64 },
+    BadRequest(String),
+    Internal(anyhow::Error),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            ApiError::NotFound => (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "not found" })),
+            )
+                .into_response(),
+            ApiError::PreconditionFailed { current_version } => {
+                let mut response = (
+                    StatusCode::PRECONDITION_FAILED,
+                    Json(serde_json::json!({
+                        "error": "If-Match precondition failed",
+                        "current_etag": etag(current_version),
+                    })),
+                )
+                    .into_response();
+                if let Ok(value) = etag(current_version).parse() {
+                    response.headers_mut().insert(axum::http::header::ETAG, value);
+                }
+                response
+            }
+            ApiError::BadRequest(msg) => (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": msg })),
+            )
+                .into_response(),
+            ApiError::Internal(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": err.to_string() })),
+            )
+                .into_response(),
+        }
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ApiError {
+    fn from(err: E) -> Self {
+        ApiError::Internal(err.into())
+    }
+}
+
+/// RFC 6902 / RFC 7386 content negotiation shared by all PATCH endpoints.
+///
+/// Serializes `current` to a `serde_json::Value`, applies `body` to it as
+/// whichever patch format the `Content-Type` header names, and
+/// deserializes the result back into `T`.
+fn apply_patch_body<T>(
+    current: &T,
+    content_type: Option<&str>,
+    body: &[u8],
+) -> Result<T, ApiError>
+where
+    T: Serialize + serde::de::DeserializeOwned,
+{
+    let mut doc = serde_json::to_value(current)?;
+    match content_type {
+        Some("application/json-patch+json") => {
+            let ops: Vec<JsonPatchOp> = serde_json::from_slice(body)
+                .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            apply_json_patch(&mut doc, &ops).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        }
+        Some("application/merge-patch+json") => {
+            let patch_doc: serde_json::Value =
+                serde_json::from_slice(body).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+            apply_merge_patch(&mut doc, &patch_doc);
+        }
+        other => {
+            return Err(ApiError::BadRequest(format!(
+                "unsupported or missing Content-Type for patch request: {:?}",
+                other
+            )));
+        }
+    }
+    serde_json::from_value(doc).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+fn content_type(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or(v).trim())
+}
+
+fn with_etag<T: Serialize>(body: T, version: u64) -> axum::response::Response {
+    let mut response = Json(body).into_response();
+    if let Ok(value) = etag(version).parse() {
+        response.headers_mut().insert(axum::http::header::ETAG, value);
+    }
+    response
+}
+
+async fn patch_agent_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<axum::response::Response, ApiError> {
+    if state.agents.get(&id).is_none() {
+        return Err(ApiError::NotFound);
+    }
+    let if_match = parse_if_match(&headers);
+    let ct = content_type(&headers).map(|s| s.to_string());
+    let result = state.agents.update(&id, if_match, |agent| {
+        *agent = apply_patch_body(agent, ct.as_deref(), &body)?;
+        Ok::<_, ApiError>(())
+    })?;
+
+    match result {
+        Ok((agent, version)) => {
+            save_persisted_store(&state.store_path, &state.agents, &state.teams);
+            Ok(with_etag(agent, version))
+        }
+        Err(current_version) => Err(ApiError::PreconditionFailed { current_version }),
+    }
+}
+
+async fn patch_conversation_handler(
+    State(state): State<AppState>,
+    Path(conversation_id): Path<String>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<axum::response::Response, ApiError> {
+    let if_match = parse_if_match(&headers);
+
+    if state.conversations.get(&conversation_id).is_none() {
+        if if_match.is_some() {
+            return Err(ApiError::NotFound);
+        }
+        state
+            .conversations
+            .seed(conversation_id.clone(), ConversationState::default());
+    }
+
+    let ct = content_type(&headers).map(|s| s.to_string());
+    let result = state
+        .conversations
+        .update(&conversation_id, if_match, |conv| {
+            *conv = apply_patch_body(conv, ct.as_deref(), &body)?;
+            Ok::<_, ApiError>(())
+        })?;
+
+    match result {
+        Ok((conv, version)) => Ok(with_etag(conv, version)),
+        Err(current_version) => Err(ApiError::PreconditionFailed { current_version }),
+    }
+}
+
+async fn delete_conversation_handler(
+    State(state): State<AppState>,
+    Path(conversation_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError> {
+    let if_match = parse_if_match(&headers);
+    match state.conversations.remove(&conversation_id, if_match) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(ApiError::NotFound),
+        Err(current_version) => Err(ApiError::PreconditionFailed { current_version }),
+    }
+}
+
+// ─── Agent/team CRUD management surface ──────────────────────────────────
+//
+// `agents`/`teams` are the same `Arc<VersionedStore<_>>` every handler in
+// this file reads from, including `chat_handler`'s agent-id check — so a
+// create/update/delete here is visible to the next request immediately,
+// with no restart and no separate reload step.
+//
+// Every handler that mutates either store also calls
+// `save_persisted_store` afterwards, writing the full agents/teams
+// snapshot to `state.store_path` (`AGENT_STORE_PATH` by default) and
+// restored via `load_persisted_store` in `HttpServer::start` — so CRUD
+// changes survive a restart instead of living only in the in-memory
+// `VersionedStore`s. There is no `Settings` writer visible in this crate
+// to write through to instead, hence the sidecar file.
+//
+// This surface still deliberately does NOT dispatch to whatever process
+// actually runs an agent — this crate only ever reads/writes
+// `AgentConfig` values and validates `AgentConfig`/`TeamConfig`
+// references against each other.
+
+async fn list_agents_handler(State(state): State<AppState>) -> Json<Vec<AgentConfig>> {
+    Json(state.agents.list().into_iter().map(|(_, a, _)| a).collect())
+}
+
+async fn create_agent_handler(
+    State(state): State<AppState>,
+    Json(agent): Json<AgentConfig>,
+) -> Result<axum::response::Response, ApiError> {
+    validate_agent_config(&agent)?;
+    match state.agents.create(agent.name.clone(), agent) {
+        Some((agent, version)) => {
+            save_persisted_store(&state.store_path, &state.agents, &state.teams);
+            let mut response = with_etag(agent, version);
+            *response.status_mut() = StatusCode::CREATED;
+            Ok(response)
+        }
+        None => Err(ApiError::BadRequest("agent id already exists".into())),
+    }
+}
+
+async fn get_agent_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<axum::response::Response, ApiError> {
+    match state.agents.get(&id) {
+        Some((agent, version)) => Ok(with_etag(agent, version)),
+        None => Err(ApiError::NotFound),
+    }
+}
+
+async fn put_agent_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(mut agent): Json<AgentConfig>,
+) -> Result<axum::response::Response, ApiError> {
+    agent.name = id.clone();
+    validate_agent_config(&agent)?;
+    let if_match = parse_if_match(&headers);
+    match state.agents.put(id, if_match, agent) {
+        Ok((agent, version)) => {
+            save_persisted_store(&state.store_path, &state.agents, &state.teams);
+            Ok(with_etag(agent, version))
+        }
+        Err(current_version) => Err(ApiError::PreconditionFailed { current_version }),
+    }
+}
+
+async fn delete_agent_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError> {
+    let if_match = parse_if_match(&headers);
+    match state.agents.remove(&id, if_match) {
+        Ok(true) => {
+            save_persisted_store(&state.store_path, &state.agents, &state.teams);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(ApiError::NotFound),
+        Err(current_version) => Err(ApiError::PreconditionFailed { current_version }),
+    }
+}
+
+/// Inspect the outgoing queue for one agent without consuming it.
+async fn agent_messages_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<OutgoingMessage>>, ApiError> {
+    if state.agents.get(&id).is_none() {
+        return Err(ApiError::NotFound);
+    }
+    let responses = state.queue.poll_outgoing("").await?;
     Ok(Json(
-        serde_json::json!({ "status": "ok", "message": "Conversation reset" }),
+        responses
+            .into_iter()
+            .map(|(_, message)| message)
+            .filter(|message| message.agent.as_deref() == Some(id.as_str()))
+            .collect(),
     ))
 }
+
+fn known_agent_ids(state: &AppState) -> std::collections::HashSet<String> {
+    state.agents.list().into_iter().map(|(id, _, _)| id).collect()
+}
+
+async fn list_teams_handler(State(state): State<AppState>) -> Json<Vec<TeamConfig>> {
+    Json(state.teams.list().into_iter().map(|(_, t, _)| t).collect())
+}
+
+async fn create_team_handler(
+    State(state): State<AppState>,
+    Json(team): Json<TeamConfig>,
+) -> Result<axum::response::Response, ApiError> {
+    validate_team_config(&team, &known_agent_ids(&state))?;
+    match state.teams.create(team.name.clone(), team) {
+        Some((team, version)) => {
+            save_persisted_store(&state.store_path, &state.agents, &state.teams);
+            let mut response = with_etag(team, version);
+            *response.status_mut() = StatusCode::CREATED;
+            Ok(response)
+        }
+        None => Err(ApiError::BadRequest("team id already exists".into())),
+    }
+}
+
+async fn get_team_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<axum::response::Response, ApiError> {
+    match state.teams.get(&id) {
+        Some((team, version)) => Ok(with_etag(team, version)),
+        None => Err(ApiError::NotFound),
+    }
+}
+
+async fn put_team_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(mut team): Json<TeamConfig>,
+) -> Result<axum::response::Response, ApiError> {
+    team.name = id.clone();
+    validate_team_config(&team, &known_agent_ids(&state))?;
+    let if_match = parse_if_match(&headers);
+    match state.teams.put(id, if_match, team) {
+        Ok((team, version)) => {
+            save_persisted_store(&state.store_path, &state.agents, &state.teams);
+            Ok(with_etag(team, version))
+        }
+        Err(current_version) => Err(ApiError::PreconditionFailed { current_version }),
+    }
+}
+
+async fn delete_team_handler(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError> {
+    let if_match = parse_if_match(&headers);
+    match state.teams.remove(&id, if_match) {
+        Ok(true) => {
+            save_persisted_store(&state.store_path, &state.agents, &state.teams);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(ApiError::NotFound),
+        Err(current_version) => Err(ApiError::PreconditionFailed { current_version }),
+    }
+}
@@ -0,0 +1,264 @@
+//! RFC 6902 JSON Patch and RFC 7386 JSON Merge Patch application.
+//!
+//! Both patch formats operate on `serde_json::Value` so they can be applied
+//! uniformly to any resource that round-trips through serde (agent/team
+//! config, conversation state) before being re-deserialized into its typed
+//! form.
+
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// A single RFC 6902 operation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum JsonPatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { path: String, from: String },
+    Copy { path: String, from: String },
+    Test { path: String, value: Value },
+}
+
+#[derive(Debug)]
+pub enum PatchError {
+    InvalidPointer(String),
+    NotFound(String),
+    TestFailed { path: String },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::InvalidPointer(p) => write!(f, "invalid JSON pointer: {}", p),
+            PatchError::NotFound(p) => write!(f, "path not found: {}", p),
+            PatchError::TestFailed { path } => write!(f, "test operation failed at {}", path),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Apply a sequence of RFC 6902 operations to `doc` in order, stopping (and
+/// leaving `doc` unmodified from the caller's perspective only up to the
+/// failing op) at the first error.
+pub fn apply_json_patch(doc: &mut Value, ops: &[JsonPatchOp]) -> Result<(), PatchError> {
+    for op in ops {
+        match op {
+            JsonPatchOp::Add { path, value } => pointer_add(doc, path, value.clone())?,
+            JsonPatchOp::Remove { path } => {
+                pointer_remove(doc, path)?;
+            }
+            JsonPatchOp::Replace { path, value } => {
+                pointer_remove(doc, path)?;
+                pointer_add(doc, path, value.clone())?;
+            }
+            JsonPatchOp::Move { path, from } => {
+                let moved = pointer_remove(doc, from)?;
+                pointer_add(doc, path, moved)?;
+            }
+            JsonPatchOp::Copy { path, from } => {
+                let copied = pointer_get(doc, from)?.clone();
+                pointer_add(doc, path, copied)?;
+            }
+            JsonPatchOp::Test { path, value } => {
+                let found = pointer_get(doc, path)?;
+                if found != value {
+                    return Err(PatchError::TestFailed { path: path.clone() });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply an RFC 7386 merge patch: recursively merge `patch` into `doc`,
+/// where a `null` value deletes the corresponding key.
+pub fn apply_merge_patch(doc: &mut Value, patch: &Value) {
+    if let Value::Object(patch_obj) = patch {
+        if !doc.is_object() {
+            *doc = Value::Object(Map::new());
+        }
+        let target = doc.as_object_mut().expect("just ensured object");
+        for (key, patch_value) in patch_obj {
+            if patch_value.is_null() {
+                target.remove(key);
+            } else {
+                let entry = target.entry(key.clone()).or_insert(Value::Null);
+                apply_merge_patch(entry, patch_value);
+            }
+        }
+    } else {
+        *doc = patch.clone();
+    }
+}
+
+fn split_pointer(pointer: &str) -> Result<Vec<String>, PatchError> {
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    if !pointer.starts_with('/') {
+        return Err(PatchError::InvalidPointer(pointer.to_string()));
+    }
+    Ok(pointer[1..]
+        .split('/')
+        .map(|tok| tok.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn pointer_get<'a>(doc: &'a Value, pointer: &str) -> Result<&'a Value, PatchError> {
+    let tokens = split_pointer(pointer)?;
+    let mut current = doc;
+    for token in &tokens {
+        current = match current {
+            Value::Object(map) => map
+                .get(token)
+                .ok_or_else(|| PatchError::NotFound(pointer.to_string()))?,
+            Value::Array(arr) => {
+                let idx: usize = token
+                    .parse()
+                    .map_err(|_| PatchError::InvalidPointer(pointer.to_string()))?;
+                arr.get(idx)
+                    .ok_or_else(|| PatchError::NotFound(pointer.to_string()))?
+            }
+            _ => return Err(PatchError::NotFound(pointer.to_string())),
+        };
+    }
+    Ok(current)
+}
+
+fn pointer_add(doc: &mut Value, pointer: &str, value: Value) -> Result<(), PatchError> {
+    let tokens = split_pointer(pointer)?;
+    if tokens.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+    let (last, parents) = tokens.split_last().unwrap();
+    let mut current = doc;
+    for token in parents {
+        current = match current {
+            Value::Object(map) => map
+                .get_mut(token)
+                .ok_or_else(|| PatchError::NotFound(pointer.to_string()))?,
+            Value::Array(arr) => {
+                let idx: usize = token
+                    .parse()
+                    .map_err(|_| PatchError::InvalidPointer(pointer.to_string()))?;
+                arr.get_mut(idx)
+                    .ok_or_else(|| PatchError::NotFound(pointer.to_string()))?
+            }
+            _ => return Err(PatchError::NotFound(pointer.to_string())),
+        };
+    }
+    match current {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        Value::Array(arr) => {
+            if last == "-" {
+                arr.push(value);
+            } else {
+                let idx: usize = last
+                    .parse()
+                    .map_err(|_| PatchError::InvalidPointer(pointer.to_string()))?;
+                if idx > arr.len() {
+                    return Err(PatchError::NotFound(pointer.to_string()));
+                }
+                arr.insert(idx, value);
+            }
+        }
+        _ => return Err(PatchError::NotFound(pointer.to_string())),
+    }
+    Ok(())
+}
+
+fn pointer_remove(doc: &mut Value, pointer: &str) -> Result<Value, PatchError> {
+    let tokens = split_pointer(pointer)?;
+    if tokens.is_empty() {
+        return Err(PatchError::InvalidPointer(pointer.to_string()));
+    }
+    let (last, parents) = tokens.split_last().unwrap();
+    let mut current = doc;
+    for token in parents {
+        current = match current {
+            Value::Object(map) => map
+                .get_mut(token)
+                .ok_or_else(|| PatchError::NotFound(pointer.to_string()))?,
+            Value::Array(arr) => {
+                let idx: usize = token
+                    .parse()
+                    .map_err(|_| PatchError::InvalidPointer(pointer.to_string()))?;
+                arr.get_mut(idx)
+                    .ok_or_else(|| PatchError::NotFound(pointer.to_string()))?
+            }
+            _ => return Err(PatchError::NotFound(pointer.to_string())),
+        };
+    }
+    match current {
+        Value::Object(map) => map
+            .remove(last)
+            .ok_or_else(|| PatchError::NotFound(pointer.to_string())),
+        Value::Array(arr) => {
+            let idx: usize = last
+                .parse()
+                .map_err(|_| PatchError::InvalidPointer(pointer.to_string()))?;
+            if idx >= arr.len() {
+                return Err(PatchError::NotFound(pointer.to_string()));
+            }
+            Ok(arr.remove(idx))
+        }
+        _ => Err(PatchError::NotFound(pointer.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_patch_add_replace_remove() {
+        let mut doc = json!({ "name": "a", "tags": ["x"] });
+        apply_json_patch(
+            &mut doc,
+            &[
+                JsonPatchOp::Replace {
+                    path: "/name".into(),
+                    value: json!("b"),
+                },
+                JsonPatchOp::Add {
+                    path: "/tags/-".into(),
+                    value: json!("y"),
+                },
+                JsonPatchOp::Remove {
+                    path: "/tags/0".into(),
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(doc, json!({ "name": "b", "tags": ["y"] }));
+    }
+
+    #[test]
+    fn test_json_patch_test_failure() {
+        let mut doc = json!({ "name": "a" });
+        let err = apply_json_patch(
+            &mut doc,
+            &[JsonPatchOp::Test {
+                path: "/name".into(),
+                value: json!("b"),
+            }],
+        )
+        .unwrap_err();
+        assert!(matches!(err, PatchError::TestFailed { .. }));
+    }
+
+    #[test]
+    fn test_merge_patch_deletes_null_keys() {
+        let mut doc = json!({ "name": "a", "model": "sonnet" });
+        apply_merge_patch(&mut doc, &json!({ "model": null, "provider": "anthropic" }));
+        assert_eq!(doc, json!({ "name": "a", "provider": "anthropic" }));
+    }
+}
@@ -117,11 +117,116 @@ pub fn diff3_hunks(scenario: &MergeScenario<&str>) -> Vec<Diff3Hunk> {
         bi += 1;
     }
 
-    coalesce_hunks(hunks)
+    coalesce_hunks(minimize_conflicts(hunks))
+}
+
+/// zdiff-style conflict minimization: shrink each `Diff3Hunk::Conflict` by
+/// factoring out the lines `left` and `right` agree on at the edges of the
+/// region into adjacent `Stable` hunks — the longest common prefix first,
+/// then the longest common suffix of what remains. If trimming empties out
+/// one side entirely, the hunk degrades to a plain `LeftChanged`/
+/// `RightChanged`. Run before `coalesce_hunks` so the freed-up stable lines
+/// merge with their neighbors.
+fn minimize_conflicts(hunks: Vec<Diff3Hunk>) -> Vec<Diff3Hunk> {
+    let mut out = Vec::with_capacity(hunks.len());
+    for hunk in hunks {
+        match hunk {
+            Diff3Hunk::Conflict { base, left, right } => {
+                let prefix_len = longest_common_prefix_len(&left, &right);
+                let prefix = left[..prefix_len].to_vec();
+                let mut left_rest = left[prefix_len..].to_vec();
+                let mut right_rest = right[prefix_len..].to_vec();
+
+                let suffix_len = longest_common_suffix_len(&left_rest, &right_rest);
+                let suffix = if suffix_len > 0 {
+                    left_rest[left_rest.len() - suffix_len..].to_vec()
+                } else {
+                    Vec::new()
+                };
+                if suffix_len > 0 {
+                    left_rest.truncate(left_rest.len() - suffix_len);
+                    right_rest.truncate(right_rest.len() - suffix_len);
+                }
+
+                if !prefix.is_empty() {
+                    out.push(Diff3Hunk::Stable(prefix));
+                }
+
+                // Only degrade to a plain one-sided hunk when a side
+                // emptied out *because trimming removed it* (prefix_len or
+                // suffix_len > 0). If a side was already empty before any
+                // trimming (e.g. a modify/delete conflict, where
+                // `diff3_hunks` hands us `Conflict{left: new, right: []}`),
+                // trimming found no shared prefix/suffix to factor out and
+                // this must stay a real `Conflict` — degrading it would
+                // silently drop the delete.
+                let trimmed_to_empty = prefix_len > 0 || suffix_len > 0;
+                if !left_rest.is_empty() || !right_rest.is_empty() {
+                    if left_rest.is_empty() && trimmed_to_empty {
+                        out.push(Diff3Hunk::RightChanged(right_rest));
+                    } else if right_rest.is_empty() && trimmed_to_empty {
+                        out.push(Diff3Hunk::LeftChanged(left_rest));
+                    } else {
+                        out.push(Diff3Hunk::Conflict {
+                            base,
+                            left: left_rest,
+                            right: right_rest,
+                        });
+                    }
+                }
+
+                if !suffix.is_empty() {
+                    out.push(Diff3Hunk::Stable(suffix));
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn longest_common_prefix_len(a: &[String], b: &[String]) -> usize {
+    a.iter().zip(b.iter()).take_while(|(x, y)| x == y).count()
+}
+
+fn longest_common_suffix_len(a: &[String], b: &[String]) -> usize {
+    let mut count = 0;
+    while count < a.len()
+        && count < b.len()
+        && a[a.len() - 1 - count] == b[b.len() - 1 - count]
+    {
+        count += 1;
+    }
+    count
+}
+
+/// How `diff3_merge_with` should emit an unresolved `Diff3Hunk::Conflict`
+/// into the final text, mirroring the side-picking gitoxide's binary merge
+/// driver exposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveWith {
+    /// Preserve today's behavior: surface `MergeResult::Conflict`.
+    Conflict,
+    /// Always take the left side.
+    Ours,
+    /// Always take the right side.
+    Theirs,
+    /// Take both sides, left then right, deduplicating an identical run so
+    /// both edits survive without repeating shared lines.
+    Union,
 }
 
 /// Perform a full three-way merge, returning a single MergeResult.
 pub fn diff3_merge(scenario: &MergeScenario<&str>) -> MergeResult {
+    diff3_merge_with(scenario, ResolveWith::Conflict)
+}
+
+/// `diff3_merge` with an explicit conflict resolution mode. `Ours`/
+/// `Theirs`/`Union` pick a deterministic side instead of surfacing
+/// `MergeResult::Conflict`, so a caller (e.g. the queue processor, for
+/// low-stakes channels like `Channel::Heartbeat`) can run fully-automatic
+/// merges where a hard conflict isn't worth it.
+pub fn diff3_merge_with(scenario: &MergeScenario<&str>, mode: ResolveWith) -> MergeResult {
     let hunks = diff3_hunks(scenario);
 
     let mut merged = String::new();
@@ -140,12 +245,47 @@ pub fn diff3_merge(scenario: &MergeScenario<&str>) -> MergeResult {
                     merged.push('\n');
                 }
             }
-            Diff3Hunk::Conflict { base, left, right } => {
-                has_conflict = true;
-                all_conflict_base.extend(base.iter().cloned());
-                all_conflict_left.extend(left.iter().cloned());
-                all_conflict_right.extend(right.iter().cloned());
-            }
+            Diff3Hunk::Conflict { base, left, right } => match mode {
+                ResolveWith::Conflict => {
+                    has_conflict = true;
+                    all_conflict_base.extend(base.iter().cloned());
+                    all_conflict_left.extend(left.iter().cloned());
+                    all_conflict_right.extend(right.iter().cloned());
+                }
+                ResolveWith::Ours => {
+                    for line in left {
+                        merged.push_str(line);
+                        merged.push('\n');
+                    }
+                }
+                ResolveWith::Theirs => {
+                    for line in right {
+                        merged.push_str(line);
+                        merged.push('\n');
+                    }
+                }
+                ResolveWith::Union => {
+                    for line in left {
+                        merged.push_str(line);
+                        merged.push('\n');
+                    }
+                    // Dedup only the boundary run: the longest prefix of
+                    // `right` that equals the suffix of `left` it directly
+                    // follows (e.g. both sides independently re-stated the
+                    // same trailing context) — not any line that happens
+                    // to recur elsewhere in `left`, which a legitimate
+                    // right-hand edit is free to do.
+                    let max_overlap = left.len().min(right.len());
+                    let overlap = (0..=max_overlap)
+                        .rev()
+                        .find(|&k| right[..k] == left[left.len() - k..])
+                        .unwrap_or(0);
+                    for line in &right[overlap..] {
+                        merged.push_str(line);
+                        merged.push('\n');
+                    }
+                }
+            },
         }
     }
 
@@ -176,6 +316,406 @@ pub fn extract_conflicts(scenario: &MergeScenario<&str>) -> Vec<MergeScenario<St
         .collect()
 }
 
+/// A general N-way merge: `n+1` "positive" sides to combine and `n`
+/// "negative" bases, interleaved as `positives[0], negatives[0],
+/// positives[1], negatives[1], ..., positives[n]`, the way jujutsu
+/// represents conflicts algebraically. A plain three-way merge is the
+/// `n = 1` case — one base, two sides — and converts losslessly via
+/// [`Merge::from_scenario`]. This generalizes `MergeScenario` for
+/// octopus-style merges of more than two branches at once; `MergeScenario`
+/// itself is unchanged and remains the stable ternary API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merge<T> {
+    positives: Vec<T>,
+    negatives: Vec<T>,
+}
+
+impl<T> Merge<T> {
+    /// Build a `Merge` from its interleaved terms. Panics if `positives`
+    /// doesn't have exactly one more term than `negatives`.
+    pub fn new(positives: Vec<T>, negatives: Vec<T>) -> Self {
+        assert_eq!(
+            positives.len(),
+            negatives.len() + 1,
+            "a Merge needs exactly one more positive term than negative terms"
+        );
+        Self { positives, negatives }
+    }
+
+    pub fn positives(&self) -> &[T] {
+        &self.positives
+    }
+
+    pub fn negatives(&self) -> &[T] {
+        &self.negatives
+    }
+}
+
+impl<'a> Merge<&'a str> {
+    /// Convert a conventional three-way `MergeScenario` into its `Merge`
+    /// form: one base (negative), two sides (positives).
+    pub fn from_scenario(scenario: &MergeScenario<&'a str>) -> Self {
+        Self::new(vec![scenario.left, scenario.right], vec![scenario.base])
+    }
+}
+
+impl<T: PartialEq + Clone> Merge<T> {
+    /// Cancel positive/negative term pairs that are equal, wherever they
+    /// sit in the term lists — jj's algebraic
+    /// conflict simplification. Returns the single remaining positive if
+    /// the merge fully resolves, or the simplified `Merge` otherwise.
+    pub fn resolve_trivial(&self) -> Result<T, Merge<T>> {
+        let mut positives = self.positives.clone();
+        let mut negatives = self.negatives.clone();
+        loop {
+            let cancel = negatives.iter().enumerate().find_map(|(ni, neg)| {
+                positives
+                    .iter()
+                    .position(|pos| pos == neg)
+                    .map(|pi| (pi, ni))
+            });
+            match cancel {
+                Some((pi, ni)) => {
+                    positives.remove(pi);
+                    negatives.remove(ni);
+                }
+                None => break,
+            }
+        }
+        if positives.len() == 1 && negatives.is_empty() {
+            Ok(positives.into_iter().next().unwrap())
+        } else {
+            Err(Merge { positives, negatives })
+        }
+    }
+}
+
+/// The outcome of reducing a k-way [`Merge`] via [`diff3_merge_n`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeResultN {
+    Resolved(String),
+    /// A flattened conflict carrying every side and base that never
+    /// resolved during the reduction, rather than just `left`/`right`.
+    Conflict {
+        bases: Vec<String>,
+        sides: Vec<String>,
+    },
+}
+
+/// Reduce a k-way [`Merge`] to a single result by repeatedly applying the
+/// pairwise diff3 algorithm against each shared base in turn: the first two
+/// positives are merged against the first negative; if that resolves
+/// cleanly, the resolved text becomes the new "left" and is merged against
+/// the next positive/negative pair, and so on. A conflict at any step short
+/// circuits the reduction and flattens every remaining positive/negative
+/// term it hasn't yet folded in, so the caller sees all unresolved sides at
+/// once instead of losing the tail of the merge.
+///
+/// Note: this returns [`MergeResultN`] rather than widening
+/// `Diff3Hunk::Conflict`/`MergeResult` themselves (those live in
+/// `crate::types` and stay ternary) — a future multi-section
+/// `Diff3Hunk::Conflict` would consume this reduction's flattened
+/// `bases`/`sides` directly.
+pub fn diff3_merge_n(merge: &Merge<&str>) -> MergeResultN {
+    if let Ok(resolved) = merge.resolve_trivial() {
+        return MergeResultN::Resolved(resolved.to_string());
+    }
+
+    let mut positives = merge.positives.iter().copied();
+    let mut current = match positives.next() {
+        Some(p) => p.to_string(),
+        None => return MergeResultN::Resolved(String::new()),
+    };
+
+    for (i, negative) in merge.negatives.iter().enumerate() {
+        let next_positive = match positives.next() {
+            Some(p) => p,
+            None => break,
+        };
+        // `diff3_merge` ends every resolved merge with '\n'; match the
+        // base's own trailing-newline convention before feeding it back in,
+        // so an unchanged last line still compares equal to the base's.
+        let left = if negative.ends_with('\n') {
+            current.as_str()
+        } else {
+            current.strip_suffix('\n').unwrap_or(current.as_str())
+        };
+        let scenario = MergeScenario::new(*negative, left, next_positive);
+        match diff3_merge(&scenario) {
+            MergeResult::Resolved(text) => current = text,
+            MergeResult::Conflict { base, left, right } => {
+                let mut bases: Vec<String> =
+                    merge.negatives[i..].iter().map(|s| s.to_string()).collect();
+                bases[0] = base;
+                let mut sides = vec![left, right];
+                sides.extend(positives.map(|p| p.to_string()));
+                return MergeResultN::Conflict { bases, sides };
+            }
+        }
+    }
+
+    MergeResultN::Resolved(current)
+}
+
+/// Render an unresolved [`MergeResultN::Conflict`] as jj-style algebraic
+/// marker text (`<<<<<<<`, alternating `+++++++`/`-------` term blocks,
+/// `>>>>>>>`) — the same format `main.rs`'s `AlgebraicConflict` machinery
+/// parses and materializes, and the one octopus/N-way conflicts already
+/// round-trip through in this repo. `Diff3Hunk`/`MergeResult` stay ternary
+/// (see the note on [`diff3_merge_n`]); this is the materialization side of
+/// that same scope decision, so a `MergeResultN::Conflict` is never stuck
+/// un-renderable.
+pub fn materialize_algebraic_merge_result(bases: &[String], sides: &[String]) -> String {
+    let mut out = String::new();
+    out.push_str("<<<<<<<\n");
+    let mut bases = bases.iter();
+    for (i, side) in sides.iter().enumerate() {
+        out.push_str("+++++++\n");
+        out.push_str(side);
+        out.push('\n');
+        if i < sides.len() - 1 {
+            if let Some(base) = bases.next() {
+                out.push_str("-------\n");
+                out.push_str(base);
+                out.push('\n');
+            }
+        }
+    }
+    out.push_str(">>>>>>>\n");
+    out
+}
+
+/// Git-style conflict marker rendering styles for [`materialize_conflicts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerStyle {
+    /// `<<<<<<<` left lines, `=======`, `>>>>>>>` right lines — no common
+    /// ancestor section.
+    Merge,
+    /// Also emits the common ancestor between `|||||||` and `=======`.
+    Diff3,
+    /// Diffs the side with fewer changed lines against `base` and shows it
+    /// as a compact `-`/`+` block, while the side with more changes is
+    /// shown verbatim — the way jj materializes conflicts by diffing
+    /// against the minority side, so a tiny conflicting edit next to a
+    /// large refactor doesn't get buried under hundreds of duplicated
+    /// lines. Falls back to [`MarkerStyle::Merge`] rendering when `base` is
+    /// empty, since there's nothing to diff against.
+    Compact,
+}
+
+/// An error parsing git-style conflict marker text back into hunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictParseError(pub String);
+
+impl std::fmt::Display for ConflictParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "conflict marker parse error: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConflictParseError {}
+
+/// Render a sequence of hunks into git-style conflict-marker text, so a
+/// conflicted `MergeResult::Conflict` can round-trip through an editor.
+/// `Stable`/`LeftChanged`/`RightChanged` hunks are emitted as plain lines;
+/// `Conflict` hunks get marker blocks, with the common-ancestor section
+/// only present in [`MarkerStyle::Diff3`].
+pub fn materialize_conflicts(hunks: &[Diff3Hunk], style: MarkerStyle) -> String {
+    let mut out = String::new();
+    for hunk in hunks {
+        match hunk {
+            Diff3Hunk::Stable(lines)
+            | Diff3Hunk::LeftChanged(lines)
+            | Diff3Hunk::RightChanged(lines) => {
+                for line in lines {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+            Diff3Hunk::Conflict { base, left, right } if style == MarkerStyle::Compact && !base.is_empty() => {
+                out.push_str(&materialize_compact_conflict(base, left, right));
+            }
+            Diff3Hunk::Conflict { base, left, right } => {
+                out.push_str("<<<<<<<\n");
+                for line in left {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                if style == MarkerStyle::Diff3 {
+                    out.push_str("|||||||\n");
+                    for line in base {
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                }
+                out.push_str("=======\n");
+                for line in right {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                out.push_str(">>>>>>>\n");
+            }
+        }
+    }
+    out
+}
+
+/// Count the changed (non-`Equal`) lines between `base` and `side`.
+fn count_changed_lines(base: &str, side: &str) -> usize {
+    TextDiff::from_lines(base, side)
+        .iter_all_changes()
+        .filter(|change| change.tag() != ChangeTag::Equal)
+        .count()
+}
+
+/// Render one conflict as a compact `-base`/`+side` diff against whichever
+/// of `left`/`right` changed less relative to `base`, with the other side
+/// shown verbatim. See [`MarkerStyle::Compact`].
+fn materialize_compact_conflict(base: &[String], left: &[String], right: &[String]) -> String {
+    let base_text = base.join("\n");
+    let left_text = left.join("\n");
+    let right_text = right.join("\n");
+
+    let left_changes = count_changed_lines(&base_text, &left_text);
+    let right_changes = count_changed_lines(&base_text, &right_text);
+
+    let (diffed_text, verbatim_lines) = if left_changes <= right_changes {
+        (left_text.as_str(), right)
+    } else {
+        (right_text.as_str(), left)
+    };
+
+    let mut out = String::new();
+    out.push_str("<<<<<<< conflict (compact)\n");
+    for change in TextDiff::from_lines(&base_text, diffed_text).iter_all_changes() {
+        let prefix = match change.tag() {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => "",
+        };
+        out.push_str(prefix);
+        out.push_str(change.value());
+        if !change.value().ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out.push_str("=======\n");
+    for line in verbatim_lines {
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(">>>>>>>\n");
+    out
+}
+
+enum MarkerParseState {
+    None,
+    Left,
+    Base,
+    Right,
+}
+
+/// Parse git-style conflict marker text back into hunks — the inverse of
+/// [`materialize_conflicts`]. Text outside marker regions becomes
+/// `Diff3Hunk::Stable` lines; each marker block becomes a
+/// `Diff3Hunk::Conflict` (with an empty `base` for `merge`-style input). A
+/// block whose left and right sections are byte-identical collapses into
+/// resolved/stable text rather than staying a conflict. Nested or
+/// unterminated marker blocks are reported as a [`ConflictParseError`].
+pub fn parse_conflicts(text: &str) -> Result<Vec<Diff3Hunk>, ConflictParseError> {
+    let mut hunks = Vec::new();
+    let mut stable_lines: Vec<String> = Vec::new();
+    let mut state = MarkerParseState::None;
+    let mut left: Vec<String> = Vec::new();
+    let mut base: Vec<String> = Vec::new();
+    let mut right: Vec<String> = Vec::new();
+
+    let lines: Vec<&str> = text.split('\n').collect();
+    // `text.split('\n')` yields a trailing "" when `text` ends with a
+    // newline; drop it so re-parsed content matches `compute_edits`'
+    // `trim_end_matches('\n')` convention and feeds cleanly back into
+    // `diff3_merge`.
+    let lines: &[&str] = if text.ends_with('\n') {
+        &lines[..lines.len() - 1]
+    } else {
+        &lines
+    };
+
+    for &line in lines {
+        match state {
+            MarkerParseState::None => {
+                if line.starts_with("<<<<<<<") {
+                    if !stable_lines.is_empty() {
+                        hunks.push(Diff3Hunk::Stable(std::mem::take(&mut stable_lines)));
+                    }
+                    state = MarkerParseState::Left;
+                } else {
+                    stable_lines.push(line.to_string());
+                }
+            }
+            MarkerParseState::Left => {
+                if line.starts_with("<<<<<<<") {
+                    return Err(ConflictParseError(
+                        "nested conflict marker inside left section".into(),
+                    ));
+                } else if line.starts_with("|||||||") {
+                    state = MarkerParseState::Base;
+                } else if line.starts_with("=======") {
+                    state = MarkerParseState::Right;
+                } else {
+                    left.push(line.to_string());
+                }
+            }
+            MarkerParseState::Base => {
+                if line.starts_with("<<<<<<<") {
+                    return Err(ConflictParseError(
+                        "nested conflict marker inside base section".into(),
+                    ));
+                } else if line.starts_with("=======") {
+                    state = MarkerParseState::Right;
+                } else {
+                    base.push(line.to_string());
+                }
+            }
+            MarkerParseState::Right => {
+                if line.starts_with("<<<<<<<") {
+                    return Err(ConflictParseError(
+                        "nested conflict marker inside right section".into(),
+                    ));
+                } else if line.starts_with(">>>>>>>") {
+                    if left == right {
+                        stable_lines.append(&mut left);
+                    } else {
+                        hunks.push(Diff3Hunk::Conflict {
+                            base: std::mem::take(&mut base),
+                            left: std::mem::take(&mut left),
+                            right: std::mem::take(&mut right),
+                        });
+                    }
+                    left.clear();
+                    base.clear();
+                    right.clear();
+                    state = MarkerParseState::None;
+                } else {
+                    right.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    if !matches!(state, MarkerParseState::None) {
+        return Err(ConflictParseError(
+            "unterminated conflict marker block".into(),
+        ));
+    }
+
+    if !stable_lines.is_empty() {
+        hunks.push(Diff3Hunk::Stable(stable_lines));
+    }
+
+    Ok(hunks)
+}
+
 // ──────────────────────────────────────────────────────────────
 // Internal: Edit representation and diff computation
 // ──────────────────────────────────────────────────────────────
@@ -472,4 +1012,335 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn test_materialize_conflicts_merge_style() {
+        let hunks = vec![
+            Diff3Hunk::Stable(vec!["keep".to_string()]),
+            Diff3Hunk::Conflict {
+                base: vec!["old".to_string()],
+                left: vec!["left1".to_string(), "left2".to_string()],
+                right: vec!["right1".to_string()],
+            },
+        ];
+        let text = materialize_conflicts(&hunks, MarkerStyle::Merge);
+        assert_eq!(
+            text,
+            "keep\n<<<<<<<\nleft1\nleft2\n=======\nright1\n>>>>>>>\n"
+        );
+    }
+
+    #[test]
+    fn test_materialize_conflicts_diff3_style_includes_base() {
+        let hunks = vec![Diff3Hunk::Conflict {
+            base: vec!["old".to_string()],
+            left: vec!["left1".to_string()],
+            right: vec!["right1".to_string()],
+        }];
+        let text = materialize_conflicts(&hunks, MarkerStyle::Diff3);
+        assert_eq!(
+            text,
+            "<<<<<<<\nleft1\n|||||||\nold\n=======\nright1\n>>>>>>>\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_conflicts_round_trips_merge_style() {
+        let hunks = vec![
+            Diff3Hunk::Stable(vec!["keep".to_string()]),
+            Diff3Hunk::Conflict {
+                base: vec![],
+                left: vec!["left1".to_string(), "left2".to_string()],
+                right: vec!["right1".to_string()],
+            },
+            Diff3Hunk::Stable(vec!["tail".to_string()]),
+        ];
+        let text = materialize_conflicts(&hunks, MarkerStyle::Merge);
+        let parsed = parse_conflicts(&text).unwrap();
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", hunks));
+    }
+
+    #[test]
+    fn test_parse_conflicts_round_trips_diff3_style() {
+        let hunks = vec![Diff3Hunk::Conflict {
+            base: vec!["old".to_string()],
+            left: vec!["left1".to_string()],
+            right: vec!["right1".to_string()],
+        }];
+        let text = materialize_conflicts(&hunks, MarkerStyle::Diff3);
+        let parsed = parse_conflicts(&text).unwrap();
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", hunks));
+    }
+
+    #[test]
+    fn test_parse_conflicts_collapses_identical_sides() {
+        let text = "<<<<<<<\nsame\n=======\nsame\n>>>>>>>\n";
+        let parsed = parse_conflicts(text).unwrap();
+        let expected = vec![Diff3Hunk::Stable(vec!["same".to_string()])];
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn test_parse_conflicts_rejects_unterminated_block() {
+        let text = "<<<<<<<\nleft\n=======\nright\n";
+        assert!(parse_conflicts(text).is_err());
+    }
+
+    #[test]
+    fn test_parse_conflicts_rejects_nested_markers() {
+        let text = "<<<<<<<\nleft\n<<<<<<<\n=======\nright\n>>>>>>>\n";
+        assert!(parse_conflicts(text).is_err());
+    }
+
+    #[test]
+    fn test_parse_conflicts_no_trailing_newline() {
+        let text = "<<<<<<<\nleft\n=======\nright\n>>>>>>>";
+        let parsed = parse_conflicts(text).unwrap();
+        let expected = vec![Diff3Hunk::Conflict {
+            base: vec![],
+            left: vec!["left".to_string()],
+            right: vec!["right".to_string()],
+        }];
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn test_merge_resolve_trivial_cancels_matching_terms() {
+        let merge = Merge::new(vec!["a", "b", "a"], vec!["a", "a"]);
+        assert_eq!(merge.resolve_trivial(), Ok("b"));
+    }
+
+    #[test]
+    fn test_merge_resolve_trivial_leaves_real_conflict() {
+        let merge = Merge::new(vec!["left", "right"], vec!["base"]);
+        assert_eq!(merge.resolve_trivial(), Err(merge));
+    }
+
+    #[test]
+    fn test_merge_from_scenario_round_trips_ternary_shape() {
+        let scenario = MergeScenario::new("base", "left", "right");
+        let merge = Merge::from_scenario(&scenario);
+        assert_eq!(merge.positives().to_vec(), vec!["left", "right"]);
+        assert_eq!(merge.negatives().to_vec(), vec!["base"]);
+    }
+
+    #[test]
+    fn test_diff3_merge_n_resolves_three_way_chain() {
+        let base = "line1\nline2\nline3";
+        let p0 = "LEFT\nline2\nline3";
+        let p1 = base;
+        let p2 = "line1\nline2\nRIGHT";
+        let merge = Merge::new(vec![p0, p1, p2], vec![base, base]);
+        let result = diff3_merge_n(&merge);
+        match result {
+            MergeResultN::Resolved(text) => {
+                assert_eq!(text, "LEFT\nline2\nRIGHT\n");
+            }
+            MergeResultN::Conflict { .. } => panic!("expected a clean k-way resolution, got conflict"),
+        }
+    }
+
+    #[test]
+    fn test_diff3_merge_n_flattens_unresolved_tail() {
+        let base = "only_line";
+        let left = "left_edit";
+        let right = "right_edit";
+        let merge = Merge::new(vec![left, right], vec![base]);
+        let result = diff3_merge_n(&merge);
+        match result {
+            MergeResultN::Conflict { sides, .. } => {
+                assert_eq!(sides, vec!["left_edit".to_string(), "right_edit".to_string()]);
+            }
+            MergeResultN::Resolved(text) => panic!("expected a conflict, got resolved: {}", text),
+        }
+    }
+
+    #[test]
+    fn test_materialize_algebraic_merge_result_renders_flattened_conflict() {
+        let base = "only_line";
+        let left = "left_edit";
+        let right = "right_edit";
+        let merge = Merge::new(vec![left, right], vec![base]);
+        let (bases, sides) = match diff3_merge_n(&merge) {
+            MergeResultN::Conflict { bases, sides } => (bases, sides),
+            MergeResultN::Resolved(text) => panic!("expected a conflict, got resolved: {}", text),
+        };
+
+        let materialized = materialize_algebraic_merge_result(&bases, &sides);
+        assert_eq!(
+            materialized,
+            "<<<<<<<\n+++++++\nleft_edit\n-------\nonly_line\n+++++++\nright_edit\n>>>>>>>\n"
+        );
+    }
+
+    #[test]
+    fn test_conflict_minimization_trims_shared_prefix_and_suffix() {
+        // Both sides replaced the same base line with a block that differs
+        // only in its middle line — the shared edge lines should be
+        // factored out of the conflict into Stable hunks.
+        let base = "base_block";
+        let left = "shared_start\nleft_middle\nshared_end";
+        let right = "shared_start\nright_middle\nshared_end";
+        let scenario = MergeScenario::new(base, left, right);
+        let hunks = diff3_hunks(&scenario);
+
+        let stable_count = hunks
+            .iter()
+            .filter(|h| matches!(h, Diff3Hunk::Stable(lines) if lines.contains(&"shared_start".to_string()) || lines.contains(&"shared_end".to_string())))
+            .count();
+        assert!(stable_count >= 1, "expected shared edges factored into Stable hunks, got: {:?}", hunks);
+
+        let conflict = hunks.iter().find(|h| matches!(h, Diff3Hunk::Conflict { .. }));
+        match conflict {
+            Some(Diff3Hunk::Conflict { left, right, .. }) => {
+                assert_eq!(left, &vec!["left_middle".to_string()]);
+                assert_eq!(right, &vec!["right_middle".to_string()]);
+            }
+            _ => panic!("expected a trimmed conflict hunk, got: {:?}", hunks),
+        }
+    }
+
+    #[test]
+    fn test_minimize_conflicts_degrades_to_left_changed_when_right_empties() {
+        let hunks = vec![Diff3Hunk::Conflict {
+            base: vec!["old".to_string()],
+            left: vec!["same".to_string(), "extra".to_string()],
+            right: vec!["same".to_string()],
+        }];
+        let minimized = minimize_conflicts(hunks);
+        assert!(minimized
+            .iter()
+            .any(|h| matches!(h, Diff3Hunk::LeftChanged(lines) if lines == &vec!["extra".to_string()])));
+        assert!(!minimized.iter().any(|h| matches!(h, Diff3Hunk::Conflict { .. })));
+    }
+
+    #[test]
+    fn test_minimize_conflicts_keeps_modify_delete_as_conflict() {
+        // No shared prefix/suffix to trim here (right is empty to begin
+        // with, a modify/delete conflict) — minimize_conflicts must not
+        // degrade this to a one-sided LeftChanged, which would silently
+        // drop the delete.
+        let hunks = vec![Diff3Hunk::Conflict {
+            base: vec!["old".to_string()],
+            left: vec!["modified_left".to_string()],
+            right: vec![],
+        }];
+        let minimized = minimize_conflicts(hunks);
+        assert!(
+            minimized
+                .iter()
+                .any(|h| matches!(h, Diff3Hunk::Conflict { .. })),
+            "expected the modify/delete conflict to survive minimization, got: {:?}",
+            minimized
+        );
+    }
+
+    #[test]
+    fn test_diff3_merge_with_ours_picks_left() {
+        let base = "keep\nmodify_me";
+        let left = "keep\nleft_wins";
+        let right = "keep\nright_wins";
+        let scenario = MergeScenario::new(base, left, right);
+        match diff3_merge_with(&scenario, ResolveWith::Ours) {
+            MergeResult::Resolved(text) => assert_eq!(text, "keep\nleft_wins\n"),
+            other => panic!("expected Resolved, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff3_merge_with_theirs_picks_right() {
+        let base = "keep\nmodify_me";
+        let left = "keep\nleft_wins";
+        let right = "keep\nright_wins";
+        let scenario = MergeScenario::new(base, left, right);
+        match diff3_merge_with(&scenario, ResolveWith::Theirs) {
+            MergeResult::Resolved(text) => assert_eq!(text, "keep\nright_wins\n"),
+            other => panic!("expected Resolved, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff3_merge_with_union_keeps_both_sides() {
+        let base = "keep\nmodify_me";
+        let left = "keep\nleft_wins";
+        let right = "keep\nright_wins";
+        let scenario = MergeScenario::new(base, left, right);
+        match diff3_merge_with(&scenario, ResolveWith::Union) {
+            MergeResult::Resolved(text) => assert_eq!(text, "keep\nleft_wins\nright_wins\n"),
+            other => panic!("expected Resolved, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff3_merge_with_union_dedupes_identical_run() {
+        // Left ends with the line right starts with, so that shared
+        // boundary line is written once rather than twice.
+        let base = "modify_me";
+        let left = "left_only\nshared";
+        let right = "shared\nright_only";
+        let scenario = MergeScenario::new(base, left, right);
+        match diff3_merge_with(&scenario, ResolveWith::Union) {
+            MergeResult::Resolved(text) => assert_eq!(text, "left_only\nshared\nright_only\n"),
+            other => panic!("expected Resolved, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff3_merge_with_union_does_not_drop_non_boundary_repeats() {
+        // "shared" appears in left and again in right, but not as a
+        // matching left-suffix/right-prefix run at the boundary between
+        // them — only a genuinely shared boundary run should be deduped,
+        // not every line that happens to appear somewhere in left.
+        let base = "modify_me";
+        let left = "shared\nleft_only";
+        let right = "right_only\nshared";
+        let scenario = MergeScenario::new(base, left, right);
+        match diff3_merge_with(&scenario, ResolveWith::Union) {
+            MergeResult::Resolved(text) => {
+                assert_eq!(text, "shared\nleft_only\nright_only\nshared\n")
+            }
+            other => panic!("expected Resolved, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_diff3_merge_default_mode_still_conflicts() {
+        let base = "keep\nmodify_me";
+        let left = "keep\nleft_wins";
+        let right = "keep\nright_wins";
+        let scenario = MergeScenario::new(base, left, right);
+        assert!(diff3_merge(&scenario).is_conflict());
+    }
+
+    #[test]
+    fn test_compact_materialization_diffs_the_smaller_side() {
+        let hunks = vec![Diff3Hunk::Conflict {
+            base: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            // left: one tiny edit against base
+            left: vec!["a".to_string(), "CHANGED".to_string(), "c".to_string()],
+            // right: a much larger rewrite against base
+            right: vec![
+                "x".to_string(),
+                "y".to_string(),
+                "z".to_string(),
+                "w".to_string(),
+            ],
+        }];
+        let text = materialize_conflicts(&hunks, MarkerStyle::Compact);
+        assert!(text.contains("-b"));
+        assert!(text.contains("+CHANGED"));
+        // the larger side is shown verbatim, not diffed
+        assert!(text.contains("x\ny\nz\nw\n"));
+    }
+
+    #[test]
+    fn test_compact_materialization_falls_back_when_base_empty() {
+        let hunks = vec![Diff3Hunk::Conflict {
+            base: vec![],
+            left: vec!["left1".to_string()],
+            right: vec!["right1".to_string()],
+        }];
+        let text = materialize_conflicts(&hunks, MarkerStyle::Compact);
+        assert_eq!(text, "<<<<<<<\nleft1\n=======\nright1\n>>>>>>>\n");
+    }
 }
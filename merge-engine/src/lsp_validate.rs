@@ -0,0 +1,351 @@
+//! Optional post-generation validation of merge candidates via a language
+//! server.
+//!
+//! This plugs into the `Resolver` pipeline as a `CandidateValidator`: once
+//! the pattern/structured/search strategies have produced `candidates` for
+//! a conflict, the configured validator gets a chance to reject any
+//! candidate whose fully-merged file introduces new error-severity
+//! diagnostics (compared to running the same language server on `base`),
+//! and to rank survivors by ascending new-diagnostic count, with ties
+//! broken by the existing strategy priority. The default (`NoOpValidator`)
+//! accepts everything unranked, which is what keeps the rest of the test
+//! suite offline.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Diagnostic severity levels as defined by the LSP spec (a subset).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub line: u32,
+}
+
+/// A merge-candidate validator, pluggable into `ResolverConfig`.
+///
+/// `validate` sees the pre-merge `base` and a fully merged candidate and
+/// returns `Some(new_error_count)` to accept it (lower ranks better, used
+/// to break ties between surviving candidates) or `None` to reject it
+/// outright.
+pub trait CandidateValidator: Send + Sync {
+    fn validate(&self, base: &str, merged: &str) -> Option<usize>;
+}
+
+/// Accepts every candidate unranked — the default, offline-friendly
+/// validator used when no language server is configured.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoOpValidator;
+
+impl CandidateValidator for NoOpValidator {
+    fn validate(&self, _base: &str, _merged: &str) -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// Rejects candidates that introduce new error-severity diagnostics
+/// relative to `base`, ranking survivors by ascending new-diagnostic
+/// count, via a minimal JSON-RPC-over-stdio LSP client.
+pub struct LspValidator {
+    command: String,
+    args: Vec<String>,
+    timeout: Duration,
+    /// LSP `languageId` sent with `textDocument/didOpen`, e.g. `"rust"` or
+    /// `"go"`. Defaults to `"plaintext"`, which most language servers
+    /// either refuse to analyze or analyze with no language-specific
+    /// diagnostics — i.e. the same accept-everything behavior as
+    /// `NoOpValidator` — so callers that know the conflict's language
+    /// should set this via [`Self::with_language_id`].
+    language_id: String,
+}
+
+impl LspValidator {
+    pub fn new(command: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            command: command.into(),
+            args,
+            timeout: Duration::from_secs(5),
+            language_id: "plaintext".to_string(),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_language_id(mut self, language_id: impl Into<String>) -> Self {
+        self.language_id = language_id.into();
+        self
+    }
+
+    /// Run the language server over `text` and return every diagnostic it
+    /// reports, regardless of severity — callers filter to the severities
+    /// they care about.
+    fn diagnostics(&self, text: &str) -> Option<Vec<Diagnostic>> {
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+
+        let result = (|| {
+            let mut session = LspSession::new(&mut child)?;
+            session.initialize()?;
+            session.did_open("file:///merge-candidate", &self.language_id, text)?;
+            session.wait_for_diagnostics(self.timeout)
+        })();
+
+        let _ = child.kill();
+        let _ = child.wait();
+
+        result
+    }
+}
+
+impl CandidateValidator for LspValidator {
+    fn validate(&self, base: &str, merged: &str) -> Option<usize> {
+        // If the server doesn't respond for `merged` at all (timeout,
+        // failed to spawn, crashed), fall back to accepting the
+        // candidate — "syntactically plausible" beats blocking a merge on
+        // a hung subprocess.
+        let merged_errors = match self.diagnostics(merged) {
+            Some(diags) => diags,
+            None => return Some(0),
+        };
+        let base_errors = self.diagnostics(base).unwrap_or_default();
+        rank_by_new_error_diagnostics(&base_errors, &merged_errors)
+    }
+}
+
+/// Reject `merged`'s diagnostics if any error-severity one isn't already
+/// present in `base`'s, ranking survivors by ascending new-diagnostic
+/// count. Compares the *set* of new diagnostics, not the overall error
+/// count — a candidate that fixes one base error while introducing a
+/// different one nets to the same count but is not actually clean, and
+/// must still be rejected. Pulled out as a pure function so this logic is
+/// unit-testable without spawning a language server.
+fn rank_by_new_error_diagnostics(base: &[Diagnostic], merged: &[Diagnostic]) -> Option<usize> {
+    let base_errors: std::collections::HashSet<&Diagnostic> = base
+        .iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Error)
+        .collect();
+    let new_diagnostic_count = merged
+        .iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Error)
+        .filter(|d| !base_errors.contains(*d))
+        .count();
+    if new_diagnostic_count > 0 {
+        None
+    } else {
+        Some(new_diagnostic_count)
+    }
+}
+
+/// One initialized JSON-RPC-over-stdio session with a spawned language
+/// server, supporting just enough of the LSP lifecycle to collect
+/// diagnostics for a single in-memory document.
+struct LspSession<'a> {
+    child: &'a mut Child,
+    next_id: u64,
+}
+
+impl<'a> LspSession<'a> {
+    fn new(child: &'a mut Child) -> Option<Self> {
+        if child.stdin.is_none() || child.stdout.is_none() {
+            return None;
+        }
+        Some(Self { child, next_id: 1 })
+    }
+
+    fn send(&mut self, payload: serde_json::Value) -> Option<()> {
+        let body = serde_json::to_vec(&payload).ok()?;
+        let stdin = self.child.stdin.as_mut()?;
+        write!(stdin, "Content-Length: {}\r\n\r\n", body.len()).ok()?;
+        stdin.write_all(&body).ok()?;
+        stdin.flush().ok()
+    }
+
+    fn initialize(&mut self) -> Option<()> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "initialize",
+            "params": { "processId": null, "rootUri": null, "capabilities": {} },
+        }))?;
+        self.send(serde_json::json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }))
+    }
+
+    fn did_open(&mut self, uri: &str, language_id: &str, text: &str) -> Option<()> {
+        self.send(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": {
+                    "uri": uri,
+                    "languageId": language_id,
+                    "version": 1,
+                    "text": text,
+                }
+            }
+        }))
+    }
+
+    /// Read frames on a background thread (so a hung server can't block
+    /// this call past `timeout`) until a
+    /// `textDocument/publishDiagnostics` notification arrives.
+    fn wait_for_diagnostics(&mut self, timeout: Duration) -> Option<Vec<Diagnostic>> {
+        let stdout = self.child.stdout.take()?;
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            while let Some(msg) = read_frame(&mut reader) {
+                let is_diagnostics =
+                    msg.get("method").and_then(|m| m.as_str()) == Some("textDocument/publishDiagnostics");
+                if is_diagnostics && tx.send(msg).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let msg = rx.recv_timeout(timeout).ok()?;
+        let diagnostics = msg.get("params")?.get("diagnostics")?.as_array()?;
+        Some(
+            diagnostics
+                .iter()
+                .map(|d| Diagnostic {
+                    severity: match d.get("severity").and_then(|s| s.as_u64()) {
+                        Some(1) => DiagnosticSeverity::Error,
+                        Some(2) => DiagnosticSeverity::Warning,
+                        Some(3) => DiagnosticSeverity::Information,
+                        _ => DiagnosticSeverity::Hint,
+                    },
+                    message: d
+                        .get("message")
+                        .and_then(|m| m.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    line: d
+                        .pointer("/range/start/line")
+                        .and_then(|l| l.as_u64())
+                        .unwrap_or(0) as u32,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Read one `Content-Length`-framed JSON-RPC message, LSP-style.
+fn read_frame<R: BufRead>(reader: &mut R) -> Option<serde_json::Value> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix("Content-Length:") {
+            content_length = rest.trim().parse::<usize>().ok();
+        }
+    }
+    let len = content_length?;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf).ok()?;
+    serde_json::from_slice(&buf).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_op_validator_accepts_everything() {
+        let validator = NoOpValidator;
+        assert_eq!(validator.validate("any base", "any merged"), Some(0));
+    }
+
+    fn error(message: &str, line: u32) -> Diagnostic {
+        Diagnostic {
+            severity: DiagnosticSeverity::Error,
+            message: message.to_string(),
+            line,
+        }
+    }
+
+    #[test]
+    fn rejects_a_candidate_that_swaps_one_base_error_for_a_different_one() {
+        // Same overall error count (one disappears, a different one
+        // appears), but the new one must still reject the candidate.
+        let base = vec![error("undefined variable `x`", 3)];
+        let merged = vec![error("undefined variable `y`", 7)];
+        assert_eq!(rank_by_new_error_diagnostics(&base, &merged), None);
+    }
+
+    #[test]
+    fn accepts_a_candidate_with_no_new_errors() {
+        let base = vec![error("undefined variable `x`", 3)];
+        let merged = vec![error("undefined variable `x`", 3)];
+        assert_eq!(rank_by_new_error_diagnostics(&base, &merged), Some(0));
+    }
+
+    #[test]
+    fn rejects_a_candidate_that_introduces_a_brand_new_error() {
+        assert_eq!(
+            rank_by_new_error_diagnostics(&[], &[error("unresolved import", 1)]),
+            None
+        );
+    }
+
+    #[test]
+    fn warnings_never_affect_the_verdict() {
+        let warning = Diagnostic {
+            severity: DiagnosticSeverity::Warning,
+            message: "unused import".to_string(),
+            line: 1,
+        };
+        assert_eq!(
+            rank_by_new_error_diagnostics(&[], std::slice::from_ref(&warning)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn read_frame_parses_one_lsp_message() {
+        let payload = serde_json::json!({ "jsonrpc": "2.0", "method": "textDocument/publishDiagnostics" });
+        let body = serde_json::to_vec(&payload).unwrap();
+        let framed = format!("Content-Length: {}\r\n\r\n", body.len());
+        let mut bytes = framed.into_bytes();
+        bytes.extend_from_slice(&body);
+
+        let mut reader = BufReader::new(&bytes[..]);
+        let parsed = read_frame(&mut reader).unwrap();
+        assert_eq!(
+            parsed["method"].as_str(),
+            Some("textDocument/publishDiagnostics")
+        );
+    }
+
+    #[test]
+    fn read_frame_returns_none_on_truncated_stream() {
+        let mut reader = BufReader::new(&b"Content-Length: 5\r\n\r\nabc"[..]);
+        assert!(read_frame(&mut reader).is_none());
+    }
+}
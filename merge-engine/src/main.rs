@@ -17,8 +17,12 @@
 //! ```
 
 use std::io::{self, Read as _};
-use std::process::ExitCode;
+use std::path::Path;
+use std::process::{Command, ExitCode};
 
+use merge_engine::diff3::{diff3_merge_n, Merge, MergeResultN};
+use merge_engine::lsp_validate::{CandidateValidator, LspValidator, NoOpValidator};
+use merge_engine::udiff::{self, AcceptedChoice};
 use merge_engine::{Language, Resolver, ResolverConfig};
 
 fn main() -> ExitCode {
@@ -28,14 +32,27 @@ fn main() -> ExitCode {
         eprintln!("merge-engine v{}", env!("CARGO_PKG_VERSION"));
         eprintln!();
         eprintln!("Usage:");
-        eprintln!("  merge-engine <base> <left> <right> [path]    Resolve conflict from files");
+        eprintln!("  merge-engine <base> <left> <right> [path] [baseLabel] [oursLabel] [theirsLabel]");
+        eprintln!("                                                Resolve conflict from files");
         eprintln!(
             "  merge-engine --stdin                          Read conflict markers from stdin"
         );
+        eprintln!(
+            "  merge-engine --interactive                    Read conflict markers from stdin, pick candidates interactively"
+        );
         eprintln!("  merge-engine --check <base> <left> <right>   Dry-run (report only)");
+        eprintln!(
+            "  merge-engine --diff <file> [path]             Structural diff of a conflict's two sides"
+        );
+        eprintln!(
+            "  merge-engine --diff <left> <right> [path]     Structural diff between two whole files"
+        );
+        eprintln!(
+            "  merge-engine --status                         List unresolved regions tracked in .merge-engine/conflicts.json"
+        );
         eprintln!();
         eprintln!("Git merge driver:");
-        eprintln!("  merge-engine %O %A %B %P");
+        eprintln!("  merge-engine %O %A %B %P %S %X %Y");
         return ExitCode::from(1);
     }
 
@@ -43,6 +60,18 @@ fn main() -> ExitCode {
         return resolve_stdin();
     }
 
+    if args[1] == "--interactive" {
+        return resolve_interactive();
+    }
+
+    if args[1] == "--diff" {
+        return run_diff_mode(&args[2..]);
+    }
+
+    if args[1] == "--status" {
+        return report_sidecar_status();
+    }
+
     let check_mode = args[1] == "--check";
     let file_args = if check_mode { &args[2..] } else { &args[1..] };
 
@@ -55,6 +84,12 @@ fn main() -> ExitCode {
     let left_path = &file_args[1];
     let right_path = &file_args[2];
     let file_path = file_args.get(3).map(|s| s.as_str());
+    // git's %S/%X/%Y-style label tokens: the ancestor/ours/theirs labels a
+    // merge driver is invoked with, so an unresolved region can be
+    // re-materialized with its original branch/revision names intact.
+    let base_label = file_args.get(4).map(|s| s.as_str());
+    let left_label = file_args.get(5).map(|s| s.as_str());
+    let right_label = file_args.get(6).map(|s| s.as_str());
 
     let base = match std::fs::read_to_string(base_path) {
         Ok(s) => s,
@@ -103,28 +138,205 @@ fn main() -> ExitCode {
                 .filter(|c| c.resolution.is_none())
                 .count();
             eprintln!("{} conflict(s) remain unresolved", unresolved);
-            println!("{}", result.merged_content);
+            println!(
+                "{}",
+                apply_conflict_labels(&result.merged_content, left_label, base_label, right_label)
+            );
             ExitCode::from(1)
         }
     } else {
         // Git merge driver mode: write result to left file (the working copy)
+        let mut sidecar = load_sidecar();
         if result.all_resolved {
             if let Err(e) = std::fs::write(left_path, &result.merged_content) {
                 eprintln!("Error writing merged result to {}: {}", left_path, e);
                 return ExitCode::from(2);
             }
+            clear_resolved_regions(&mut sidecar, left_path);
+            save_sidecar(&sidecar);
+            ExitCode::SUCCESS
+        } else if let Some(resolved) =
+            try_external_merge_tool_fallback(&resolver, &base, &left, &right, &sidecar)
+        {
+            if let Err(e) = std::fs::write(left_path, &resolved) {
+                eprintln!("Error writing merged result to {}: {}", left_path, e);
+                return ExitCode::from(2);
+            }
+            clear_resolved_regions(&mut sidecar, left_path);
+            save_sidecar(&sidecar);
             ExitCode::SUCCESS
         } else {
-            // Write partial merge with conflict markers
-            if let Err(e) = std::fs::write(left_path, &result.merged_content) {
+            // Write partial merge with conflict markers, with original
+            // ours/base/theirs labels restored if the driver was invoked
+            // with them.
+            let labeled =
+                apply_conflict_labels(&result.merged_content, left_label, base_label, right_label);
+            if let Err(e) = std::fs::write(left_path, &labeled) {
                 eprintln!("Error writing partial merge to {}: {}", left_path, e);
                 return ExitCode::from(2);
             }
+            record_unresolved_regions(&mut sidecar, left_path, &labeled);
+            save_sidecar(&sidecar);
             ExitCode::from(1)
         }
     }
 }
 
+/// One `[merge-engine.tools]` entry: an external merge tool invoked as
+/// `$command %base %left %right %output`, with the `%`-tokens substituted
+/// for real temp file paths.
+#[derive(Debug, Clone)]
+struct MergeToolConfig {
+    command: String,
+    /// If set, the tool only edits conflict markers in place rather than
+    /// producing a fully resolved file — its output gets re-parsed with
+    /// `parse_conflict_markers` and any remaining regions fed back through
+    /// the resolver.
+    tool_edits_conflict_markers: bool,
+}
+
+/// Parse a minimal `[merge-engine.tools]` config block — a hand-rolled,
+/// line-oriented reader (not a full TOML parser), just enough for the
+/// handful of keys this subsystem needs:
+/// ```ini
+/// [merge-engine.tools]
+/// command = "mytool %base %left %right %output"
+/// toolEditsConflictMarkers = true
+/// ```
+fn parse_tools_config(text: &str) -> Option<MergeToolConfig> {
+    let mut in_section = false;
+    let mut command = None;
+    let mut tool_edits_conflict_markers = false;
+
+    for raw_line in text.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_section = line.trim_start_matches('[').trim_end_matches(']') == "merge-engine.tools";
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"');
+            match key.trim() {
+                "command" => command = Some(value.to_string()),
+                "toolEditsConflictMarkers" => tool_edits_conflict_markers = value == "true",
+                _ => {}
+            }
+        }
+    }
+
+    Some(MergeToolConfig {
+        command: command?,
+        tool_edits_conflict_markers,
+    })
+}
+
+/// Load the `[merge-engine.tools]` config, if configured, from the path in
+/// `$MERGE_ENGINE_CONFIG` or the conventional `.merge-engine.toml` in the
+/// current directory.
+fn load_tools_config() -> Option<MergeToolConfig> {
+    let path = std::env::var("MERGE_ENGINE_CONFIG").unwrap_or_else(|_| ".merge-engine.toml".to_string());
+    let text = std::fs::read_to_string(path).ok()?;
+    parse_tools_config(&text)
+}
+
+/// Substitute the `%base`/`%left`/`%right`/`%output` tokens in a tool
+/// command template with real file paths.
+fn expand_tool_tokens(template: &str, base: &Path, left: &Path, right: &Path, output: &Path) -> String {
+    template
+        .replace("%base", &base.display().to_string())
+        .replace("%left", &left.display().to_string())
+        .replace("%right", &right.display().to_string())
+        .replace("%output", &output.display().to_string())
+}
+
+/// Write `base`/`left`/`right` to a fresh temp directory, launch the
+/// configured external tool, and read back its `%output` file. Returns the
+/// tool's raw output text on a clean exit.
+fn run_external_tool(tool: &MergeToolConfig, base: &str, left: &str, right: &str) -> Option<String> {
+    let dir = std::env::temp_dir().join(format!("merge-engine-tool-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).ok()?;
+
+    let base_path = dir.join("base");
+    let left_path = dir.join("left");
+    let right_path = dir.join("right");
+    let output_path = dir.join("output");
+
+    std::fs::write(&base_path, base).ok()?;
+    std::fs::write(&left_path, left).ok()?;
+    std::fs::write(&right_path, right).ok()?;
+    // Conventional merge-driver starting point, so a tool that only makes
+    // incremental edits has something to start from.
+    std::fs::write(&output_path, left).ok()?;
+
+    let expanded = expand_tool_tokens(&tool.command, &base_path, &left_path, &right_path, &output_path);
+    let mut parts = expanded.split_whitespace();
+    let program = parts.next()?;
+
+    let status = Command::new(program).args(parts).status().ok()?;
+    let output = if status.success() {
+        std::fs::read_to_string(&output_path).ok()
+    } else {
+        None
+    };
+
+    let _ = std::fs::remove_dir_all(&dir);
+    output
+}
+
+/// On an unresolved merge, fall back to the configured external merge
+/// tool: launch it on `base`/`left`/`right` and read back its output. If
+/// `toolEditsConflictMarkers` is set, the output is re-parsed with
+/// `parse_conflict_markers` and any still-unresolved regions are fed back
+/// through the resolver one more time; otherwise the tool's output is
+/// trusted verbatim. Returns `None` if no tool is configured or the tool
+/// couldn't fully resolve the merge.
+fn try_external_merge_tool_fallback(
+    resolver: &Resolver,
+    base: &str,
+    left: &str,
+    right: &str,
+    sidecar: &SidecarState,
+) -> Option<String> {
+    let tool = load_tools_config()?;
+    let output = run_external_tool(&tool, base, left, right)?;
+
+    if !tool.tool_edits_conflict_markers {
+        return Some(output);
+    }
+
+    let remaining = parse_conflict_markers(&output);
+    if remaining.is_empty() {
+        return Some(output);
+    }
+
+    let tracked = tracked_hashes(sidecar);
+    let mut resolved = output;
+    for conflict in remaining.iter().rev() {
+        let hash = content_hash(&conflict.base, &conflict.left, &conflict.right);
+        if tracked.contains(&hash) {
+            // A previous run already tracked this exact region as
+            // unresolved; re-invoking the external tool and resolver on
+            // unchanged content would just fail the same way again, so
+            // leave it for the human instead of paying for that round-trip.
+            return None;
+        }
+        let result = resolver.resolve_conflict(&conflict.base, &conflict.left, &conflict.right);
+        match &result.resolution {
+            Some(resolution) => {
+                resolved = resolved.replace(&conflict.full_marker, &resolution.content)
+            }
+            None => return None,
+        }
+    }
+    Some(resolved)
+}
+
 /// Read conflict markers from stdin and attempt to resolve.
 fn resolve_stdin() -> ExitCode {
     let mut input = String::new();
@@ -133,24 +345,147 @@ fn resolve_stdin() -> ExitCode {
         return ExitCode::from(2);
     }
 
-    // Parse conflict markers
+    // Parse conflict markers — classic 3-way git markers and jj-style
+    // algebraic (N-way) markers can't overlap (the algebraic parser never
+    // matches `|||||||`/`=======`), so it's safe to scan for both.
     let conflicts = parse_conflict_markers(&input);
-    if conflicts.is_empty() {
+    let algebraic_conflicts = parse_algebraic_conflicts(&input);
+    if conflicts.is_empty() && algebraic_conflicts.is_empty() {
         eprintln!("No conflict markers found in input");
         println!("{}", input);
         return ExitCode::SUCCESS;
     }
 
     let resolver = Resolver::new(ResolverConfig::default());
+    let validator = configured_candidate_validator();
+    let tracked = tracked_hashes(&load_sidecar());
     let mut output = input.clone();
     let mut all_resolved = true;
 
-    for (base, left, right, full_marker) in conflicts.iter().rev() {
-        let result = resolver.resolve_conflict(base, left, right);
+    for conflict in conflicts.iter().rev() {
+        let hash = content_hash(&conflict.base, &conflict.left, &conflict.right);
+        if tracked.contains(&hash) {
+            // A git-driver run already tracked this exact region as
+            // unresolved; it hasn't changed since, so skip straight to
+            // leaving its markers in place rather than re-running the
+            // resolver/validator on content we already know it rejects.
+            all_resolved = false;
+            continue;
+        }
+        let result = resolver.resolve_conflict(&conflict.base, &conflict.left, &conflict.right);
         if let Some(resolution) = &result.resolution {
-            output = output.replace(full_marker, &resolution.content);
+            output = output.replace(&conflict.full_marker, &resolution.content);
         } else {
+            // No single candidate stood out to the resolver's own
+            // strategies — give the configured CandidateValidator a
+            // post-generation pass over what it did produce, rejecting any
+            // candidate that introduces new diagnostics and ranking
+            // survivors by ascending new-diagnostic count (ties kept in
+            // the resolver's own strategy-priority order).
+            let candidates: Vec<String> =
+                result.candidates.iter().map(|c| c.content.clone()).collect();
+            match select_validated_candidate(validator.as_ref(), &conflict.base, &candidates) {
+                Some(validated) => output = output.replace(&conflict.full_marker, &validated),
+                None => all_resolved = false,
+            }
+        }
+    }
+
+    for conflict in algebraic_conflicts.iter().rev() {
+        match resolve_algebraic_conflict(conflict) {
+            Some(resolved) => output = output.replace(&conflict.full_marker, &resolved),
+            None => {
+                all_resolved = false;
+                // Re-materialize rather than leaving `full_marker` in place.
+                // Term bodies come back verbatim from `raw_lines`; marker
+                // lines come back bare (see `materialize_algebraic_conflict`).
+                // `full_marker` has no trailing newline, so drop the one the
+                // materialized text ends with.
+                let text = materialize_algebraic_conflict(conflict);
+                output = output.replace(
+                    &conflict.full_marker,
+                    text.strip_suffix('\n').unwrap_or(text.as_str()),
+                );
+            }
+        }
+    }
+
+    print!("{}", output);
+    if all_resolved {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
+
+/// Read conflict markers from stdin and resolve each unresolved region
+/// interactively: render every candidate as a unified diff against the
+/// conflict's base and let the user pick one (via an external fuzzy
+/// selector if `$MERGE_ENGINE_SELECTOR`/`fzf` is available, otherwise a
+/// numbered prompt). Accepted choices are also written to a replay patch
+/// file so the session can be reproduced non-interactively.
+fn resolve_interactive() -> ExitCode {
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("Error reading stdin: {}", e);
+        return ExitCode::from(2);
+    }
+
+    let conflicts = parse_conflict_markers(&input);
+    if conflicts.is_empty() {
+        eprintln!("No conflict markers found in input");
+        println!("{}", input);
+        return ExitCode::SUCCESS;
+    }
+
+    let selector_bin =
+        std::env::var("MERGE_ENGINE_SELECTOR").unwrap_or_else(|_| "fzf".to_string());
+    let resolver = Resolver::new(ResolverConfig::default());
+    let tracked = tracked_hashes(&load_sidecar());
+    let mut output = input.clone();
+    let mut accepted = Vec::new();
+    let mut all_resolved = true;
+
+    for (index, conflict) in conflicts.iter().enumerate().rev() {
+        let hash = content_hash(&conflict.base, &conflict.left, &conflict.right);
+        if tracked.contains(&hash) {
+            eprintln!(
+                "Conflict #{} already tracked as unresolved from a previous run, skipping",
+                index
+            );
+            all_resolved = false;
+            continue;
+        }
+        let result =
+            resolver.resolve_conflict(&conflict.base, &conflict.left, &conflict.right);
+        let candidates: Vec<String> = result.candidates.iter().map(|c| c.content.clone()).collect();
+
+        if candidates.is_empty() {
             all_resolved = false;
+            continue;
+        }
+
+        match udiff::pick_candidate(&conflict.base, &candidates, &selector_bin) {
+            Some(chosen) if chosen < candidates.len() => {
+                output = output.replace(&conflict.full_marker, &candidates[chosen]);
+                accepted.push(AcceptedChoice {
+                    conflict_index: index,
+                    candidate_index: chosen,
+                    content: candidates[chosen].clone(),
+                });
+            }
+            _ => {
+                eprintln!("No candidate selected for conflict #{}", index);
+                all_resolved = false;
+            }
+        }
+    }
+
+    accepted.reverse();
+    if !accepted.is_empty() {
+        let patch_path = std::path::Path::new(".merge-engine-interactive.patch.jsonl");
+        if let Err(e) = udiff::write_replay_patch(patch_path, &accepted) {
+            eprintln!("Warning: failed to write replay patch: {}", e);
         }
     }
 
@@ -162,8 +497,403 @@ fn resolve_stdin() -> ExitCode {
     }
 }
 
-/// Parse git conflict markers from text, returning (base, left, right, full_marker).
-fn parse_conflict_markers(text: &str) -> Vec<(String, String, String, String)> {
+/// `--diff` mode: instead of resolving, show *what actually changed*
+/// between the two conflicting sides. With one file argument, that file is
+/// expected to still contain conflict markers: every region's left side is
+/// stitched onto the surrounding text to reconstruct the full "left"
+/// document, and likewise for "right". With two file arguments, both are
+/// read as already-whole documents (no marker parsing). Either way the two
+/// documents are then diffed at the granularity of `split_into_nodes` and
+/// the changed nodes are printed — reusing the crate's `Language`
+/// detection so the node granularity can eventually be made
+/// language-aware, the same extension point `main()` already uses to pick
+/// a `ResolverConfig::language`.
+fn run_diff_mode(diff_args: &[String]) -> ExitCode {
+    if diff_args.is_empty() {
+        eprintln!("Error: --diff needs a file with conflict markers, or <left> <right>");
+        return ExitCode::from(1);
+    }
+
+    let (left_doc, right_doc, path_for_language) = if diff_args.len() == 1 {
+        let path = &diff_args[0];
+        let text = match std::fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", path, e);
+                return ExitCode::from(2);
+            }
+        };
+        let conflicts = parse_conflict_markers(&text);
+        if conflicts.is_empty() {
+            eprintln!("No conflict markers found in {} — nothing to diff", path);
+            return ExitCode::from(1);
+        }
+        let mut left_doc = text.clone();
+        let mut right_doc = text;
+        for conflict in conflicts.iter().rev() {
+            left_doc = left_doc.replace(&conflict.full_marker, &conflict.left);
+            right_doc = right_doc.replace(&conflict.full_marker, &conflict.right);
+        }
+        (left_doc, right_doc, Some(path.clone()))
+    } else {
+        let left_path = &diff_args[0];
+        let right_path = &diff_args[1];
+        let left_doc = match std::fs::read_to_string(left_path) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", left_path, e);
+                return ExitCode::from(2);
+            }
+        };
+        let right_doc = match std::fs::read_to_string(right_path) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", right_path, e);
+                return ExitCode::from(2);
+            }
+        };
+        (left_doc, right_doc, diff_args.get(2).cloned())
+    };
+
+    let language = path_for_language
+        .as_deref()
+        .and_then(|p| p.rsplit('.').next().and_then(Language::from_extension));
+
+    let entries = structural_diff(&left_doc, &right_doc, language);
+    if entries.is_empty() {
+        eprintln!("No structural changes between left and right");
+        return ExitCode::SUCCESS;
+    }
+    print!("{}", render_structural_diff(&entries));
+    ExitCode::from(1)
+}
+
+/// One changed unit between two documents' node sequences.
+enum StructuralDiffEntry {
+    Added { index: usize, content: String },
+    Removed { index: usize, content: String },
+    Changed { index: usize, before: String, after: String },
+}
+
+/// Split `text` into top-level "nodes" — for now a language-agnostic
+/// heuristic (consecutive non-blank lines form one node, blank lines
+/// separate nodes), which approximates top-level declarations in most of
+/// the languages `Language` recognizes. `language` is accepted as an
+/// extension point for giving individual languages a proper grammar-aware
+/// split later, the same way `NoOpValidator` accepts but doesn't use a
+/// base/merged pair — it's not consulted yet.
+fn split_into_nodes(text: &str, _language: Option<Language>) -> Vec<String> {
+    let mut nodes = Vec::new();
+    let mut current = Vec::new();
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                nodes.push(current.join("\n"));
+                current.clear();
+            }
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        nodes.push(current.join("\n"));
+    }
+    nodes
+}
+
+/// Diff `left` and `right` at the granularity of `split_into_nodes`,
+/// returning only the nodes that actually differ.
+fn structural_diff(
+    left: &str,
+    right: &str,
+    language: Option<Language>,
+) -> Vec<StructuralDiffEntry> {
+    let left_nodes = split_into_nodes(left, language);
+    let right_nodes = split_into_nodes(right, language);
+
+    let diff = similar::capture_diff_slices(similar::Algorithm::Myers, &left_nodes, &right_nodes);
+    let mut entries = Vec::new();
+
+    for op in diff {
+        match op {
+            similar::DiffOp::Equal { .. } => {}
+            similar::DiffOp::Insert {
+                new_index, new_len, ..
+            } => {
+                for i in 0..new_len {
+                    entries.push(StructuralDiffEntry::Added {
+                        index: new_index + i,
+                        content: right_nodes[new_index + i].clone(),
+                    });
+                }
+            }
+            similar::DiffOp::Delete {
+                old_index, old_len, ..
+            } => {
+                for i in 0..old_len {
+                    entries.push(StructuralDiffEntry::Removed {
+                        index: old_index + i,
+                        content: left_nodes[old_index + i].clone(),
+                    });
+                }
+            }
+            similar::DiffOp::Replace {
+                old_index,
+                old_len,
+                new_index,
+                new_len,
+            } => {
+                for i in 0..old_len.max(new_len) {
+                    match (i < old_len, i < new_len) {
+                        (true, true) => entries.push(StructuralDiffEntry::Changed {
+                            index: old_index + i,
+                            before: left_nodes[old_index + i].clone(),
+                            after: right_nodes[new_index + i].clone(),
+                        }),
+                        (true, false) => entries.push(StructuralDiffEntry::Removed {
+                            index: old_index + i,
+                            content: left_nodes[old_index + i].clone(),
+                        }),
+                        (false, true) => entries.push(StructuralDiffEntry::Added {
+                            index: new_index + i,
+                            content: right_nodes[new_index + i].clone(),
+                        }),
+                        (false, false) => unreachable!(),
+                    }
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+/// Render `structural_diff`'s output as a human-readable report of changed
+/// nodes, one `+`/`-`/`~` section per entry.
+fn render_structural_diff(entries: &[StructuralDiffEntry]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        match entry {
+            StructuralDiffEntry::Added { index, content } => {
+                out.push_str(&format!("+ node[{}] (added on right)\n", index));
+                for line in content.lines() {
+                    out.push_str(&format!("+ {}\n", line));
+                }
+            }
+            StructuralDiffEntry::Removed { index, content } => {
+                out.push_str(&format!("- node[{}] (removed on right)\n", index));
+                for line in content.lines() {
+                    out.push_str(&format!("- {}\n", line));
+                }
+            }
+            StructuralDiffEntry::Changed { index, before, after } => {
+                out.push_str(&format!("~ node[{}] changed\n", index));
+                for line in before.lines() {
+                    out.push_str(&format!("- {}\n", line));
+                }
+                for line in after.lines() {
+                    out.push_str(&format!("+ {}\n", line));
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Default location of the conflict-tracking sidecar, relative to the
+/// current directory — analogous to how a VCS tracks outstanding
+/// conflicted paths, but scoped to individual regions within a file so a
+/// large merge can be resolved incrementally across invocations.
+const SIDECAR_PATH: &str = ".merge-engine/conflicts.json";
+
+/// One unresolved conflict region tracked across invocations, identified
+/// by a stable hash of its base/left/right content rather than its
+/// position (so it still matches up after earlier regions in the same
+/// file are resolved and the file shifts around it).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct TrackedConflict {
+    hash: String,
+    byte_start: usize,
+    byte_end: usize,
+}
+
+/// The full sidecar: per-file lists of still-unresolved regions.
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+struct SidecarState {
+    files: std::collections::BTreeMap<String, Vec<TrackedConflict>>,
+}
+
+/// Load the sidecar from [`SIDECAR_PATH`], or an empty state if it doesn't
+/// exist or fails to parse.
+fn load_sidecar() -> SidecarState {
+    std::fs::read_to_string(SIDECAR_PATH)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Every region hash currently tracked as still-unresolved, across every
+/// file in the sidecar. A hash identifies a region by content, not by
+/// path (see [`TrackedConflict`]), so this is checked globally rather
+/// than per file — callers that resolve conflict markers on their own
+/// (stdin/interactive mode, an external tool's own leftover markers)
+/// don't necessarily know which file they came from, but can still
+/// recognize "a previous run already tried and failed on this exact
+/// region" and skip burning another resolver/LSP-validator pass on it.
+fn tracked_hashes(state: &SidecarState) -> std::collections::HashSet<String> {
+    state
+        .files
+        .values()
+        .flat_map(|regions| regions.iter().map(|r| r.hash.clone()))
+        .collect()
+}
+
+/// Write the sidecar back to [`SIDECAR_PATH`], creating its parent
+/// directory as needed — or delete it entirely once there's nothing left
+/// to track, so a fully-resolved tree has no stray state file behind.
+fn save_sidecar(state: &SidecarState) {
+    if state.files.is_empty() {
+        let _ = std::fs::remove_file(SIDECAR_PATH);
+        return;
+    }
+    if let Some(parent) = Path::new(SIDECAR_PATH).parent() {
+        if std::fs::create_dir_all(parent).is_err() {
+            return;
+        }
+    }
+    if let Ok(json) = serde_json::to_string_pretty(state) {
+        let _ = std::fs::write(SIDECAR_PATH, json);
+    }
+}
+
+/// A stable (across runs, not across Rust versions/platforms) hash of one
+/// region's three-way content, used as its identity in the sidecar.
+fn content_hash(base: &str, left: &str, right: &str) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    base.hash(&mut hasher);
+    left.hash(&mut hasher);
+    right.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// After a run still leaves `file_path` with conflict markers in
+/// `merged_content`, replace its sidecar entry with exactly the regions
+/// still unresolved. A region a human already resolved by hand — or that
+/// the resolver settled this time — simply won't appear in
+/// `merged_content` anymore, so it's dropped from the sidecar by omission
+/// rather than needing to be matched and removed explicitly.
+fn record_unresolved_regions(state: &mut SidecarState, file_path: &str, merged_content: &str) {
+    let mut tracked = Vec::new();
+    let mut cursor = 0usize;
+    for conflict in parse_conflict_markers(merged_content) {
+        if let Some(offset) = merged_content[cursor..].find(&conflict.full_marker) {
+            let byte_start = cursor + offset;
+            let byte_end = byte_start + conflict.full_marker.len();
+            cursor = byte_end;
+            tracked.push(TrackedConflict {
+                hash: content_hash(&conflict.base, &conflict.left, &conflict.right),
+                byte_start,
+                byte_end,
+            });
+        }
+    }
+
+    if tracked.is_empty() {
+        state.files.remove(file_path);
+    } else {
+        state.files.insert(file_path.to_string(), tracked);
+    }
+}
+
+/// Drop all tracked regions for a file that just came out fully resolved.
+fn clear_resolved_regions(state: &mut SidecarState, file_path: &str) {
+    state.files.remove(file_path);
+}
+
+/// `--status`: report how many conflict regions the sidecar still tracks,
+/// and in which files. Exits non-zero while any remain, so it can be used
+/// as a CI gate the same way `git status --porcelain` is.
+fn report_sidecar_status() -> ExitCode {
+    let sidecar = load_sidecar();
+    if sidecar.files.is_empty() {
+        println!("No unresolved conflict regions tracked");
+        return ExitCode::SUCCESS;
+    }
+
+    let total: usize = sidecar.files.values().map(|regions| regions.len()).sum();
+    println!("{} unresolved conflict region(s) across {} file(s):", total, sidecar.files.len());
+    for (path, regions) in &sidecar.files {
+        println!("  {} ({} region(s))", path, regions.len());
+    }
+    ExitCode::from(1)
+}
+
+/// One parsed classic-conflict region, including whatever trailing label
+/// text (e.g. a branch/revision name) git itself put on the
+/// `<<<<<<<`/`|||||||`/`>>>>>>>` lines, so a caller that re-emits the
+/// region can restore those labels instead of only ever knowing about
+/// driver-supplied ones.
+struct ParsedConflict {
+    base: String,
+    left: String,
+    right: String,
+    full_marker: String,
+    left_label: Option<String>,
+    base_label: Option<String>,
+    right_label: Option<String>,
+}
+
+/// Build the `CandidateValidator` to use for this run: an `LspValidator`
+/// spawning `$MERGE_ENGINE_LSP_COMMAND` (space-separated, e.g.
+/// `"rust-analyzer"`) if that's set, otherwise the no-op default — the
+/// same "offline unless explicitly configured" pattern as
+/// `load_tools_config`'s `$MERGE_ENGINE_CONFIG`.
+///
+/// `--stdin`/`--interactive` have no file path to infer a language from,
+/// so the `languageId` the validator opens documents with (otherwise the
+/// LSP default of `"plaintext"`, which most servers won't produce
+/// language-specific diagnostics for) comes from `$MERGE_ENGINE_LSP_LANGUAGE`
+/// (e.g. `"rust"`, `"go"`) if that's set.
+fn configured_candidate_validator() -> Box<dyn CandidateValidator> {
+    let Ok(command_line) = std::env::var("MERGE_ENGINE_LSP_COMMAND") else {
+        return Box::new(NoOpValidator);
+    };
+    let mut parts = command_line.split_whitespace();
+    match parts.next() {
+        Some(program) => {
+            let mut validator = LspValidator::new(program, parts.map(str::to_string).collect());
+            if let Ok(language_id) = std::env::var("MERGE_ENGINE_LSP_LANGUAGE") {
+                validator = validator.with_language_id(language_id);
+            }
+            Box::new(validator)
+        }
+        None => Box::new(NoOpValidator),
+    }
+}
+
+/// Pick the best of `candidates` (already in the resolver's own
+/// strategy-priority order) through `validator`: reject any that
+/// introduce new diagnostics, and among the survivors keep the one with
+/// the fewest new diagnostics, breaking ties by priority (i.e. the
+/// earliest-indexed survivor, since `min_by_key` keeps the first minimum).
+fn select_validated_candidate(
+    validator: &dyn CandidateValidator,
+    base: &str,
+    candidates: &[String],
+) -> Option<String> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| validator.validate(base, c).map(|new_diagnostics| (new_diagnostics, i)))
+        .min_by_key(|&(new_diagnostics, i)| (new_diagnostics, i))
+        .map(|(_, i)| candidates[i].clone())
+}
+
+/// Parse git conflict markers from text.
+fn parse_conflict_markers(text: &str) -> Vec<ParsedConflict> {
     let mut conflicts = Vec::new();
     let lines = text.lines();
     let mut marker_lines = Vec::new();
@@ -171,6 +901,9 @@ fn parse_conflict_markers(text: &str) -> Vec<(String, String, String, String)> {
     let mut left_lines = Vec::new();
     let mut base_lines = Vec::new();
     let mut right_lines = Vec::new();
+    let mut left_label = None;
+    let mut base_label = None;
+    let mut right_label = None;
 
     for line in lines {
         match state {
@@ -181,11 +914,15 @@ fn parse_conflict_markers(text: &str) -> Vec<(String, String, String, String)> {
                     left_lines.clear();
                     base_lines.clear();
                     right_lines.clear();
+                    left_label = marker_label(line, "<<<<<<<");
+                    base_label = None;
+                    right_label = None;
                 }
             }
             MarkerState::Left => {
                 marker_lines.push(line);
                 if line.starts_with("|||||||") {
+                    base_label = marker_label(line, "|||||||");
                     state = MarkerState::Base;
                 } else if line.starts_with("=======") {
                     state = MarkerState::Right;
@@ -204,13 +941,17 @@ fn parse_conflict_markers(text: &str) -> Vec<(String, String, String, String)> {
             MarkerState::Right => {
                 marker_lines.push(line);
                 if line.starts_with(">>>>>>>") {
+                    right_label = marker_label(line, ">>>>>>>");
                     let full_marker = marker_lines.join("\n");
-                    conflicts.push((
-                        base_lines.join("\n"),
-                        left_lines.join("\n"),
-                        right_lines.join("\n"),
+                    conflicts.push(ParsedConflict {
+                        base: base_lines.join("\n"),
+                        left: left_lines.join("\n"),
+                        right: right_lines.join("\n"),
                         full_marker,
-                    ));
+                        left_label: left_label.take(),
+                        base_label: base_label.take(),
+                        right_label: right_label.take(),
+                    });
                     marker_lines.clear();
                     state = MarkerState::None;
                 } else {
@@ -223,9 +964,285 @@ fn parse_conflict_markers(text: &str) -> Vec<(String, String, String, String)> {
     conflicts
 }
 
+/// The trailing label text on a marker line, if any, e.g.
+/// `marker_label("<<<<<<< HEAD", "<<<<<<<") == Some("HEAD")`.
+fn marker_label(line: &str, prefix: &str) -> Option<String> {
+    let label = line.strip_prefix(prefix)?.trim();
+    if label.is_empty() {
+        None
+    } else {
+        Some(label.to_string())
+    }
+}
+
 enum MarkerState {
     None,
     Left,
     Base,
     Right,
 }
+
+/// Splice git's `%S`/`%X`/`%Y`-style ancestor/ours/theirs labels onto an
+/// unresolved region's `<<<<<<<`/`|||||||`/`>>>>>>>` marker lines, the way
+/// git itself labels a conflict it couldn't merge. A CLI-supplied label
+/// always wins where given (it's the ground truth from the merge driver
+/// invocation); a marker line that already carries some other label (e.g.
+/// one the resolver wrote, or one round-tripped from the original input)
+/// keeps it when no CLI override is given, rather than being blanked out.
+fn apply_conflict_labels(
+    text: &str,
+    left_label: Option<&str>,
+    base_label: Option<&str>,
+    right_label: Option<&str>,
+) -> String {
+    if left_label.is_none() && base_label.is_none() && right_label.is_none() {
+        return text.to_string();
+    }
+
+    text.lines()
+        .map(|line| {
+            if line.starts_with("<<<<<<<") {
+                label_marker_line(line, "<<<<<<<", left_label)
+            } else if line.starts_with("|||||||") {
+                label_marker_line(line, "|||||||", base_label)
+            } else if line.starts_with(">>>>>>>") {
+                label_marker_line(line, ">>>>>>>", right_label)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn label_marker_line(line: &str, prefix: &str, override_label: Option<&str>) -> String {
+    let existing = marker_label(line, prefix);
+    match override_label.or(existing.as_deref()) {
+        Some(label) => format!("{} {}", prefix, label),
+        None => prefix.to_string(),
+    }
+}
+
+/// One term of a jj-style algebraic (N-way) conflict region: Jujutsu
+/// represents a conflict as `n+1` "add" terms interleaved with `n` "remove"
+/// terms, each either a verbatim snapshot or a diff against the preceding
+/// term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AlgebraicTermKind {
+    Add,
+    Remove,
+}
+
+/// A single parsed term. `content` is the fully reconstructed snapshot
+/// (diff terms are already resolved against the preceding term), used to
+/// feed the resolver; `raw_lines` is the literal source body, kept so an
+/// unresolved region can be re-materialized byte-for-byte.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AlgebraicTerm {
+    kind: AlgebraicTermKind,
+    content: String,
+    raw_lines: Vec<String>,
+    is_diff: bool,
+}
+
+/// One parsed jj-style algebraic conflict region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AlgebraicConflict {
+    terms: Vec<AlgebraicTerm>,
+    full_marker: String,
+}
+
+impl AlgebraicConflict {
+    /// A region is well-formed only if it has exactly one more add term
+    /// than remove terms.
+    fn is_well_formed(&self) -> bool {
+        let adds = self
+            .terms
+            .iter()
+            .filter(|t| t.kind == AlgebraicTermKind::Add)
+            .count();
+        let removes = self
+            .terms
+            .iter()
+            .filter(|t| t.kind == AlgebraicTermKind::Remove)
+            .count();
+        adds == removes + 1
+    }
+}
+
+enum AlgebraicMarkerState {
+    None,
+    AwaitingTerm,
+    InTerm(AlgebraicTermKind, bool),
+}
+
+/// Parse jj-style algebraic conflict regions out of `text`: `<<<<<<<`
+/// followed by a sequence of `+++++++` (add), `-------` (remove), or
+/// `%%%%%%%` (diff against the preceding term) blocks, terminated by
+/// `>>>>>>>`. Unlike `parse_conflict_markers`'s fixed `(base,left,right)`
+/// tuple, this returns every term in order so octopus-style regions with
+/// more than two sides round-trip.
+fn parse_algebraic_conflicts(text: &str) -> Vec<AlgebraicConflict> {
+    let mut conflicts = Vec::new();
+    let mut state = AlgebraicMarkerState::None;
+    let mut marker_lines: Vec<&str> = Vec::new();
+    let mut terms: Vec<AlgebraicTerm> = Vec::new();
+    let mut current_lines: Vec<String> = Vec::new();
+    let mut prev_content = String::new();
+
+    for line in text.lines() {
+        match state {
+            AlgebraicMarkerState::None => {
+                if line.starts_with("<<<<<<<") {
+                    marker_lines.clear();
+                    marker_lines.push(line);
+                    terms.clear();
+                    prev_content.clear();
+                    state = AlgebraicMarkerState::AwaitingTerm;
+                }
+            }
+            AlgebraicMarkerState::AwaitingTerm => {
+                marker_lines.push(line);
+                if line.starts_with("+++++++") {
+                    current_lines.clear();
+                    state = AlgebraicMarkerState::InTerm(AlgebraicTermKind::Add, false);
+                } else if line.starts_with("-------") {
+                    current_lines.clear();
+                    state = AlgebraicMarkerState::InTerm(AlgebraicTermKind::Remove, false);
+                } else if line.starts_with("%%%%%%%") {
+                    current_lines.clear();
+                    state = AlgebraicMarkerState::InTerm(AlgebraicTermKind::Add, true);
+                } else if line.starts_with(">>>>>>>") {
+                    let full_marker = marker_lines.join("\n");
+                    conflicts.push(AlgebraicConflict {
+                        terms: std::mem::take(&mut terms),
+                        full_marker,
+                    });
+                    state = AlgebraicMarkerState::None;
+                } else if line.starts_with("|||||||") || line.starts_with("=======") {
+                    // A classic git region, not an algebraic one: leave it
+                    // to `parse_conflict_markers`.
+                    state = AlgebraicMarkerState::None;
+                }
+                // Any other line here is a stray/malformed marker region;
+                // ignore it and keep waiting, mirroring the best-effort
+                // style of `parse_conflict_markers`.
+            }
+            AlgebraicMarkerState::InTerm(kind, is_diff) => {
+                marker_lines.push(line);
+                let is_term_boundary = line.starts_with("+++++++")
+                    || line.starts_with("-------")
+                    || line.starts_with("%%%%%%%")
+                    || line.starts_with(">>>>>>>");
+                if is_term_boundary {
+                    let lines = std::mem::take(&mut current_lines);
+                    let content = if is_diff {
+                        apply_hunk_lines(&prev_content, &lines)
+                    } else {
+                        lines.join("\n")
+                    };
+                    terms.push(AlgebraicTerm {
+                        kind,
+                        content: content.clone(),
+                        raw_lines: lines,
+                        is_diff,
+                    });
+                    prev_content = content;
+
+                    if line.starts_with(">>>>>>>") {
+                        let full_marker = marker_lines.join("\n");
+                        conflicts.push(AlgebraicConflict {
+                            terms: std::mem::take(&mut terms),
+                            full_marker,
+                        });
+                        state = AlgebraicMarkerState::None;
+                    } else if line.starts_with("+++++++") {
+                        state = AlgebraicMarkerState::InTerm(AlgebraicTermKind::Add, false);
+                    } else if line.starts_with("-------") {
+                        state = AlgebraicMarkerState::InTerm(AlgebraicTermKind::Remove, false);
+                    } else {
+                        state = AlgebraicMarkerState::InTerm(AlgebraicTermKind::Add, true);
+                    }
+                } else {
+                    current_lines.push(line.to_string());
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Apply a `%%%%%%%` diff term's hunk lines (` ` context, `-` removed,
+/// `+` added) to `base`, reconstructing the term's snapshot content.
+fn apply_hunk_lines(base: &str, hunk_lines: &[String]) -> String {
+    let mut base_lines = base.lines();
+    let mut out: Vec<String> = Vec::new();
+    for hline in hunk_lines {
+        if let Some(added) = hline.strip_prefix('+') {
+            out.push(added.to_string());
+        } else if hline.starts_with('-') {
+            base_lines.next();
+        } else {
+            let ctx = hline.strip_prefix(' ').unwrap_or(hline.as_str());
+            base_lines.next();
+            out.push(ctx.to_string());
+        }
+    }
+    out.join("\n")
+}
+
+/// Render a parsed [`AlgebraicConflict`] back into jj-style marker text,
+/// with each term's body taken verbatim from its `raw_lines`. Marker lines
+/// are written bare: any label text that followed a marker in the parsed
+/// input (e.g. `<<<<<<< Conflict 1 of 1`) is not reproduced.
+fn materialize_algebraic_conflict(conflict: &AlgebraicConflict) -> String {
+    let mut out = String::new();
+    out.push_str("<<<<<<<\n");
+    for term in &conflict.terms {
+        if term.is_diff {
+            out.push_str("%%%%%%%\n");
+        } else {
+            match term.kind {
+                AlgebraicTermKind::Add => out.push_str("+++++++\n"),
+                AlgebraicTermKind::Remove => out.push_str("-------\n"),
+            }
+        }
+        for line in &term.raw_lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out.push_str(">>>>>>>\n");
+    out
+}
+
+/// Resolve an algebraic conflict by reducing its `n+1` adds / `n` removes
+/// through the general k-way diff3 reduction (`diff3::Merge` /
+/// `diff3_merge_n`). Returns `None` for a malformed region or one that
+/// still conflicts after reduction, leaving the caller to fall back to
+/// [`materialize_algebraic_conflict`].
+fn resolve_algebraic_conflict(conflict: &AlgebraicConflict) -> Option<String> {
+    if !conflict.is_well_formed() {
+        return None;
+    }
+
+    let positives: Vec<&str> = conflict
+        .terms
+        .iter()
+        .filter(|t| t.kind == AlgebraicTermKind::Add)
+        .map(|t| t.content.as_str())
+        .collect();
+    let negatives: Vec<&str> = conflict
+        .terms
+        .iter()
+        .filter(|t| t.kind == AlgebraicTermKind::Remove)
+        .map(|t| t.content.as_str())
+        .collect();
+
+    let merge = Merge::new(positives, negatives);
+    match diff3_merge_n(&merge) {
+        MergeResultN::Resolved(text) => Some(text),
+        MergeResultN::Conflict { .. } => None,
+    }
+}
@@ -0,0 +1,151 @@
+//! Unified-diff rendering for merge candidates, plus an interactive
+//! fuzzy-picker CLI mode for choosing between them.
+//!
+//! `Resolver` can produce several candidate resolutions for one conflict;
+//! this module lets a human actually look at them (as a standard unified
+//! diff against the conflict's base region) and pick one, either through an
+//! external fuzzy selector (fzf-style) or, if none is available, a plain
+//! numbered prompt.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use similar::TextDiff;
+
+/// Render `candidate` as a unified diff against `base`, in standard
+/// `@@ -a,b +c,d @@` form with `context_radius` lines of context around
+/// each change.
+pub fn unified_diff(base: &str, candidate: &str, context_radius: usize) -> String {
+    TextDiff::from_lines(base, candidate)
+        .unified_diff()
+        .context_radius(context_radius)
+        .to_string()
+}
+
+/// `unified_diff` with the repo's default context radius of 3 lines.
+pub fn render_candidate_diff(base: &str, candidate: &str) -> String {
+    unified_diff(base, candidate, 3)
+}
+
+/// One accepted choice, recorded so the resolution can be replayed
+/// non-interactively later.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AcceptedChoice {
+    pub conflict_index: usize,
+    pub candidate_index: usize,
+    pub content: String,
+}
+
+/// Let a human choose among `candidates` for one conflict region. Tries an
+/// external fuzzy selector (`selector_bin`, fzf-style: choices on stdin,
+/// the selected line on stdout) first, and falls back to a numbered prompt
+/// if the selector binary isn't available or isn't picked up by the shell.
+/// Returns the index of the chosen candidate.
+pub fn pick_candidate(base: &str, candidates: &[String], selector_bin: &str) -> Option<usize> {
+    let diffs: Vec<String> = candidates
+        .iter()
+        .map(|c| render_candidate_diff(base, c))
+        .collect();
+
+    if let Some(choice) = pick_via_fuzzy_selector(selector_bin, &diffs) {
+        return Some(choice);
+    }
+    pick_via_numbered_prompt(&diffs)
+}
+
+/// Write accepted choices out as a small JSON-lines patch file so a batch
+/// run can replay the same resolutions without re-prompting.
+pub fn write_replay_patch(path: &std::path::Path, choices: &[AcceptedChoice]) -> io::Result<()> {
+    let mut out = String::new();
+    for choice in choices {
+        out.push_str(&serde_json::to_string(choice).expect("AcceptedChoice always serializes"));
+        out.push('\n');
+    }
+    std::fs::write(path, out)
+}
+
+fn pick_via_fuzzy_selector(selector_bin: &str, choices: &[String]) -> Option<usize> {
+    let mut child = Command::new(selector_bin)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    {
+        let stdin = child.stdin.as_mut()?;
+        for (i, diff) in choices.iter().enumerate() {
+            let summary = diff.lines().find(|l| l.starts_with("@@")).unwrap_or("(no changes)");
+            writeln!(stdin, "[{}] {}", i, summary).ok()?;
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let selected = String::from_utf8(output.stdout).ok()?;
+    let line = selected.lines().next()?.trim();
+    let idx_str = line.strip_prefix('[')?.split(']').next()?;
+    idx_str.parse().ok()
+}
+
+fn pick_via_numbered_prompt(choices: &[String]) -> Option<usize> {
+    for (i, diff) in choices.iter().enumerate() {
+        println!("--- candidate {} ---", i);
+        println!("{}", diff);
+    }
+    print!("Pick a candidate [0-{}]: ", choices.len().checked_sub(1)?);
+    io::stdout().flush().ok()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).ok()?;
+    input.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_diff_emits_standard_hunk_header() {
+        let base = "a\nb\nc\nd\ne";
+        let candidate = "a\nb\nCHANGED\nd\ne";
+        let diff = unified_diff(base, candidate, 1);
+        assert!(diff.contains("@@"));
+        assert!(diff.contains("-c"));
+        assert!(diff.contains("+CHANGED"));
+        // context radius of 1 keeps the untouched "e" line out of the hunk
+        assert!(!diff.contains(" e"));
+    }
+
+    #[test]
+    fn default_context_radius_is_three() {
+        let base = (1..=10).map(|n| n.to_string()).collect::<Vec<_>>().join("\n");
+        let mut lines: Vec<&str> = base.split('\n').collect();
+        lines[5] = "CHANGED";
+        let candidate = lines.join("\n");
+        let diff = render_candidate_diff(&base, &candidate);
+        // lines 3,4,5 before and 7,8,9 after the change (0-indexed line 5)
+        assert!(diff.contains('3'));
+        assert!(diff.contains('9'));
+    }
+
+    #[test]
+    fn replay_patch_round_trips_through_json_lines() {
+        let choices = vec![
+            AcceptedChoice { conflict_index: 0, candidate_index: 1, content: "resolved a".into() },
+            AcceptedChoice { conflict_index: 1, candidate_index: 0, content: "resolved b".into() },
+        ];
+        let dir = std::env::temp_dir().join(format!("udiff-test-{:p}", &choices));
+        write_replay_patch(&dir, &choices).unwrap();
+        let contents = std::fs::read_to_string(&dir).unwrap();
+        let replayed: Vec<AcceptedChoice> = contents
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        std::fs::remove_file(&dir).unwrap();
+        assert_eq!(replayed.len(), 2);
+        assert_eq!(replayed[1].content, "resolved b");
+    }
+}